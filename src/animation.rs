@@ -22,10 +22,6 @@ impl AnimationCharacterMap {
     pub fn insert(&mut self, key_entity: Entity, value_entity: Entity) {
         self.0.insert(key_entity, value_entity);
     }
-
-    pub fn len(&self) -> usize {
-        self.0.len()
-    }
 }
 
 #[derive(Event)]
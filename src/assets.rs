@@ -1,30 +1,38 @@
 use bevy::prelude::*;
 use bevy_asset_loader::prelude::*;
+use bevy_common_assets::ron::RonAssetPlugin;
 
 use crate::core::GameState;
+use crate::physics::MovementProfile;
 
 pub struct AssetPlugin;
 
 impl Plugin for AssetPlugin {
     fn build(&self, app: &mut App) {
-        app.add_loading_state(
-            LoadingState::new(GameState::Preload).continue_to_state(GameState::Gameplay),
-        )
-        .add_collection_to_loading_state::<_, CharacterCache>(GameState::Preload)
-        .add_collection_to_loading_state::<_, PlayerAnimationCache>(GameState::Preload)
-        .add_collection_to_loading_state::<_, MaterialCache>(GameState::Preload)
-        .add_dynamic_collection_to_loading_state::<_, StandardDynamicAssetCollection>(
-            GameState::Preload,
-            "manifests/character_models.assets.ron",
-        )
-        .add_dynamic_collection_to_loading_state::<_, StandardDynamicAssetCollection>(
-            GameState::Preload,
-            "manifests/player_animations.assets.ron",
-        )
-        .add_dynamic_collection_to_loading_state::<_, StandardDynamicAssetCollection>(
-            GameState::Preload,
-            "manifests/materials.assets.ron",
-        );
+        app.add_plugins(RonAssetPlugin::<MovementProfile>::new(&["movement.ron"]))
+            .add_loading_state(
+                LoadingState::new(GameState::Preload).continue_to_state(GameState::Gameplay),
+            )
+            .add_collection_to_loading_state::<_, CharacterCache>(GameState::Preload)
+            .add_collection_to_loading_state::<_, PlayerAnimationCache>(GameState::Preload)
+            .add_collection_to_loading_state::<_, MaterialCache>(GameState::Preload)
+            .add_collection_to_loading_state::<_, MovementProfileCache>(GameState::Preload)
+            .add_dynamic_collection_to_loading_state::<_, StandardDynamicAssetCollection>(
+                GameState::Preload,
+                "manifests/character_models.assets.ron",
+            )
+            .add_dynamic_collection_to_loading_state::<_, StandardDynamicAssetCollection>(
+                GameState::Preload,
+                "manifests/player_animations.assets.ron",
+            )
+            .add_dynamic_collection_to_loading_state::<_, StandardDynamicAssetCollection>(
+                GameState::Preload,
+                "manifests/materials.assets.ron",
+            )
+            .add_dynamic_collection_to_loading_state::<_, StandardDynamicAssetCollection>(
+                GameState::Preload,
+                "manifests/movement_profiles.assets.ron",
+            );
     }
 }
 
@@ -47,3 +55,11 @@ pub struct PlayerAnimationCache {
     #[asset(key = "run")]
     pub run: Handle<AnimationClip>,
 }
+
+#[derive(Resource, AssetCollection)]
+pub struct MovementProfileCache {
+    #[asset(key = "player_movement")]
+    pub player: Handle<MovementProfile>,
+    #[asset(key = "player_swim_movement")]
+    pub swim: Handle<MovementProfile>,
+}
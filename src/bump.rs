@@ -0,0 +1,133 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use crate::{
+    assets::MaterialCache,
+    carry::Carryable,
+    core::{GameState, Unit},
+    particles::{OneTimeParticleBundle, ParticleCache},
+    physics::{FixedMotion, Gravity, Grounded, MotionSet},
+    player::{handle_jump, Player, PlayerState, GROUND_SKIN},
+};
+
+const HEAD_PROBE_RADIUS: f32 = 0.4;
+const HEAD_REACH: f32 = 1.0;
+
+#[derive(Event)]
+pub struct HeadBumpEvent {
+    pub entity: Entity,
+    pub other: Entity,
+    pub position: Vec3,
+}
+
+#[derive(Component)]
+pub enum BumpReaction {
+    Breakable,
+    Release { released: bool },
+    Bounce(f32),
+}
+
+fn handle_head_bump(
+    fixed_time: Res<FixedTime>,
+    rapier_context: Res<RapierContext>,
+    mut head_bump_events: EventWriter<HeadBumpEvent>,
+    mut player_query: Query<
+        (Entity, &Player, &Transform, &mut FixedMotion, &Gravity),
+        Without<Grounded>,
+    >,
+) {
+    for (entity, player, transform, mut motion, gravity) in &mut player_query {
+        let rise = gravity.rise(&motion);
+        if rise <= Unit::ZERO || player.state == PlayerState::Hanging {
+            continue;
+        }
+
+        let filter = QueryFilter::exclude_dynamic()
+            .exclude_sensors()
+            .exclude_collider(entity);
+        let max_toi = HEAD_REACH - HEAD_PROBE_RADIUS
+            + GROUND_SKIN
+            + f32::from(rise) * fixed_time.period.as_secs_f32();
+
+        if let Some((other, toi)) = rapier_context.cast_shape(
+            transform.translation,
+            Quat::IDENTITY,
+            gravity.up,
+            &Collider::ball(HEAD_PROBE_RADIUS),
+            max_toi,
+            filter,
+        ) {
+            gravity.set_rise(&mut motion, Unit::ZERO);
+            head_bump_events.send(HeadBumpEvent {
+                entity,
+                other,
+                position: transform.translation + gravity.up * (toi.toi + HEAD_PROBE_RADIUS),
+            });
+        }
+    }
+}
+
+fn handle_bump_reactions(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    materials: Res<MaterialCache>,
+    mut head_bump_events: EventReader<HeadBumpEvent>,
+    particles: Res<ParticleCache>,
+    mut reaction_query: Query<(&mut BumpReaction, &Transform), Without<Player>>,
+    mut player_query: Query<(&mut FixedMotion, &Gravity), With<Player>>,
+) {
+    for event in head_bump_events.iter() {
+        let Ok((mut reaction, transform)) = reaction_query.get_mut(event.other) else {
+            continue;
+        };
+        let Ok((mut motion, gravity)) = player_query.get_mut(event.entity) else {
+            continue;
+        };
+
+        match *reaction {
+            BumpReaction::Breakable => {
+                commands.spawn(OneTimeParticleBundle::new(
+                    event.position,
+                    2.0,
+                    particles.dust.clone_weak(),
+                ));
+                commands.entity(event.other).despawn_recursive();
+            }
+            BumpReaction::Release { released: false } => {
+                *reaction = BumpReaction::Release { released: true };
+                commands
+                    .spawn(PbrBundle {
+                        mesh: meshes.add(Mesh::from(shape::Cube::new(0.5))),
+                        material: materials.checkerboard.clone_weak(),
+                        transform: Transform::from_translation(
+                            transform.translation + gravity.up * 1.0,
+                        ),
+                        ..default()
+                    })
+                    .insert(Collider::cuboid(0.25, 0.25, 0.25))
+                    .insert(RigidBody::Dynamic)
+                    .insert(Carryable);
+            }
+            BumpReaction::Release { released: true } => (),
+            BumpReaction::Bounce(bounce_velocity) => {
+                gravity.set_rise(&mut motion, Unit::from(-bounce_velocity));
+            }
+        }
+    }
+}
+
+pub struct BumpPlugin;
+
+impl Plugin for BumpPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<HeadBumpEvent>().add_systems(
+            FixedUpdate,
+            (
+                handle_head_bump.after(handle_jump),
+                handle_bump_reactions.after(handle_head_bump),
+            )
+                .in_set(MotionSet::React)
+                .run_if(in_state(GameState::Gameplay)),
+        );
+    }
+}
@@ -5,6 +5,9 @@ use crate::player::PlayerData;
 use bevy::prelude::*;
 use leafwing_input_manager::prelude::*;
 
+const UNDERWATER_DISTANCE: f32 = 6.0;
+const UNDERWATER_HEIGHT: f32 = 1.5;
+
 #[derive(Component, Default)]
 pub struct MainCamera {
     offset: Vec3,
@@ -55,7 +58,16 @@ fn update_camera_desired_position(
     player_data: Res<PlayerData>,
 ) {
     for mut camera in &mut camera_query {
-        let mut starting_transform = Transform::from_translation(player_data.player_position);
+        // underwater the camera trails directly behind the player so it can follow them diving
+        // and surfacing
+        if player_data.underwater {
+            camera.desired_position = player_data.camera_target
+                - player_data.player_forward * UNDERWATER_DISTANCE
+                + Vec3::Y * UNDERWATER_HEIGHT;
+            continue;
+        }
+
+        let mut starting_transform = Transform::from_translation(player_data.camera_target);
 
         starting_transform.rotation = Quat::default();
         starting_transform.rotate_y(camera.angle.to_radians());
@@ -78,7 +90,7 @@ fn position_camera(
                     time.delta_seconds() * camera.easing,
                 );
                 transform.translation = lerped_position;
-                transform.look_at(player_data.player_position, Vec3::Y);
+                transform.look_at(player_data.camera_target, Vec3::Y);
             }
             _ => (),
         }
@@ -110,7 +122,7 @@ fn rotate_camera(
                     let angle_i16 = camera.angle as i16;
                     let angle_difference = angle_i16 % 45;
                     let angle_change = if angle_difference <= 22 {
-                        -1 * angle_difference
+                        -angle_difference
                     } else {
                         45 - angle_difference
                     };
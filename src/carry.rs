@@ -0,0 +1,200 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use crate::{
+    core::{GameState, IndexPointer},
+    health::{take_damage, RespawnEvent},
+    input::{PlayerAction, TickActions},
+    physics::{Grounded, MotionSet, Speed},
+    player::{Player, PlayerData, PlayerState},
+};
+
+const HOLD_OFFSET: Vec3 = Vec3::new(0.0, 0.4, -0.9);
+const PICKUP_REACH: f32 = 0.9;
+const PICKUP_RADIUS: f32 = 0.6;
+const CARRY_SPEED_FACTOR: f32 = 0.5;
+const THROW_SPEED: f32 = 6.0;
+const THROW_LIFT: f32 = 3.0;
+
+#[derive(Component)]
+pub struct Carryable;
+
+fn pick_up_objects(
+    mut commands: Commands,
+    mut player_data: ResMut<PlayerData>,
+    rapier_context: Res<RapierContext>,
+    mut player_query: Query<
+        (Entity, &mut Player, &mut Speed, &Transform, &TickActions),
+        With<Grounded>,
+    >,
+    carryable_query: Query<&GlobalTransform, With<Carryable>>,
+) {
+    for (entity, mut player, mut speed, transform, action) in &mut player_query {
+        let can_pick_up = matches!(
+            player.state,
+            PlayerState::Idle | PlayerState::Walking | PlayerState::Running
+        ) && matches!(player_data.held_object_index, IndexPointer::Empty);
+
+        if !can_pick_up || !action.just_pressed(PlayerAction::Interact) {
+            continue;
+        }
+
+        let search_position = transform.translation + transform.forward() * PICKUP_REACH;
+        let is_carryable = |candidate| carryable_query.contains(candidate);
+        let filter = QueryFilter::default()
+            .exclude_sensors()
+            .exclude_collider(entity)
+            .predicate(&is_carryable);
+
+        let mut nearest_object: Option<(Entity, f32)> = None;
+        rapier_context.intersections_with_shape(
+            search_position,
+            Quat::IDENTITY,
+            &Collider::ball(PICKUP_RADIUS),
+            filter,
+            |object| {
+                if let Ok(object_transform) = carryable_query.get(object) {
+                    let distance = object_transform.translation().distance(search_position);
+                    if nearest_object.is_none_or(|(_, nearest)| distance < nearest) {
+                        nearest_object = Some((object, distance));
+                    }
+                }
+                true
+            },
+        );
+
+        if let Some((object, _)) = nearest_object {
+            player.state = PlayerState::Carrying;
+            player_data.held_object_index = IndexPointer::WaitFor(object);
+            speed.scale_max(CARRY_SPEED_FACTOR);
+
+            commands
+                .entity(object)
+                .insert((RigidBody::KinematicPositionBased, ColliderDisabled));
+            commands.entity(entity).add_child(object);
+        }
+    }
+}
+
+type CarryableQuery<'w, 's> =
+    Query<'w, 's, (&'static mut Transform, &'static Parent), (With<Carryable>, Without<Player>)>;
+
+fn hold_objects(
+    mut player_data: ResMut<PlayerData>,
+    player_query: Query<(Entity, &Transform), With<Player>>,
+    mut carryable_query: CarryableQuery,
+) {
+    for (player_entity, player_transform) in &player_query {
+        match player_data.held_object_index {
+            IndexPointer::WaitFor(object) => {
+                if let Ok((mut object_transform, parent)) = carryable_query.get_mut(object) {
+                    if parent.get() == player_entity {
+                        *object_transform = Transform::from_translation(HOLD_OFFSET);
+                        player_data.held_object_index = IndexPointer::FindAt(object);
+                    }
+                }
+            }
+            IndexPointer::FindAt(_) => (),
+            IndexPointer::Empty => continue,
+        }
+
+        player_data.held_object_position = player_transform.transform_point(HOLD_OFFSET);
+    }
+}
+
+fn release_objects(
+    mut commands: Commands,
+    mut player_data: ResMut<PlayerData>,
+    mut player_query: Query<(&mut Player, &mut Speed, &Transform, &TickActions)>,
+    mut carryable_query: Query<&mut Transform, (With<Carryable>, Without<Player>)>,
+) {
+    for (mut player, mut speed, player_transform, action) in &mut player_query {
+        let object = match player_data.held_object_index {
+            IndexPointer::FindAt(object) | IndexPointer::WaitFor(object) => object,
+            IndexPointer::Empty => continue,
+        };
+
+        let is_throwing = player.is_free_moving() && action.just_pressed(PlayerAction::Interact);
+
+        if player.is_free_moving() && !is_throwing {
+            continue;
+        }
+
+        let Ok(mut object_transform) = carryable_query.get_mut(object) else {
+            player_data.held_object_index = IndexPointer::Empty;
+            continue;
+        };
+
+        *object_transform = Transform::from_translation(player_data.held_object_position)
+            .with_rotation(player_transform.rotation);
+
+        commands
+            .entity(object)
+            .remove_parent()
+            .remove::<ColliderDisabled>()
+            .insert(RigidBody::Dynamic);
+
+        if is_throwing {
+            commands.entity(object).insert(Velocity {
+                linvel: player_transform.forward() * THROW_SPEED + Vec3::Y * THROW_LIFT,
+                ..default()
+            });
+            if player.state == PlayerState::Carrying {
+                player.state = PlayerState::Idle;
+            }
+        }
+
+        player_data.held_object_index = IndexPointer::Empty;
+        speed.reset_max();
+    }
+}
+
+fn drop_on_respawn(
+    mut commands: Commands,
+    mut player_data: ResMut<PlayerData>,
+    mut respawn_events: EventReader<RespawnEvent>,
+    player_query: Query<(), With<Player>>,
+    mut carryable_query: Query<(&mut Transform, &GlobalTransform), With<Carryable>>,
+) {
+    for event in respawn_events.iter() {
+        if !player_query.contains(event.entity) {
+            continue;
+        }
+
+        let object = match player_data.held_object_index {
+            IndexPointer::FindAt(object) | IndexPointer::WaitFor(object) => object,
+            IndexPointer::Empty => continue,
+        };
+        player_data.held_object_index = IndexPointer::Empty;
+
+        let Ok((mut object_transform, object_global)) = carryable_query.get_mut(object) else {
+            continue;
+        };
+
+        // left behind where the player went down
+        *object_transform = object_global.compute_transform();
+        commands
+            .entity(object)
+            .remove_parent()
+            .remove::<ColliderDisabled>()
+            .insert(RigidBody::Dynamic);
+    }
+}
+
+pub struct CarryPlugin;
+
+impl Plugin for CarryPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            (
+                release_objects.before(pick_up_objects),
+                pick_up_objects,
+                hold_objects.after(pick_up_objects),
+                drop_on_respawn.after(take_damage).before(release_objects),
+            )
+                .in_set(MotionSet::React)
+                .run_if(in_state(GameState::Gameplay)),
+        );
+    }
+}
@@ -16,23 +16,58 @@ pub enum GameState {
 pub enum IndexPointer {
     #[default]
     Empty,
-    FindAt(usize),
-    WaitFor(usize),
+    FindAt(Entity),
+    WaitFor(Entity),
 }
 
-#[derive(Default, Debug)]
+pub const UNITS_PER_METER: i32 = 26;
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Unit(pub i32);
 
+impl Unit {
+    pub const ZERO: Unit = Unit(0);
+
+    pub fn div_rem(self, divisor: i32) -> (Unit, Unit) {
+        (
+            Unit(self.0.saturating_div(divisor)),
+            Unit(self.0.wrapping_rem(divisor)),
+        )
+    }
+
+    pub fn scale(self, factor: f32) -> Unit {
+        Unit(saturate((self.0 as f64 * factor as f64).round() as i128))
+    }
+}
+
+fn saturate(value: i128) -> i32 {
+    value.clamp(i32::MIN as i128, i32::MAX as i128) as i32
+}
+
+fn isqrt(value: i128) -> i128 {
+    if value <= 0 {
+        return 0;
+    }
+
+    let mut root = value;
+    let mut next = root / 2 + root % 2;
+    while next < root {
+        root = next;
+        next = (root + value / root) / 2;
+    }
+    root
+}
+
 impl std::ops::Add for Unit {
     type Output = Self;
     fn add(self, rhs: Self) -> Self::Output {
-        Unit(self.0 + rhs.0)
+        Unit(self.0.saturating_add(rhs.0))
     }
 }
 
 impl std::ops::AddAssign for Unit {
     fn add_assign(&mut self, rhs: Self) {
-        self.0 += rhs.0;
+        *self = *self + rhs;
     }
 }
 
@@ -40,19 +75,63 @@ impl std::ops::Sub for Unit {
     type Output = Self;
 
     fn sub(self, rhs: Self) -> Self::Output {
-        Unit(self.0 - rhs.0)
+        Unit(self.0.saturating_sub(rhs.0))
     }
 }
 
 impl std::ops::SubAssign for Unit {
     fn sub_assign(&mut self, rhs: Self) {
-        self.0 -= rhs.0;
+        *self = *self - rhs;
+    }
+}
+
+impl std::ops::Mul for Unit {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Unit(saturate(
+            self.0 as i128 * rhs.0 as i128 / UNITS_PER_METER as i128,
+        ))
+    }
+}
+
+impl std::ops::Div for Unit {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        Unit(saturate(
+            self.0 as i128 * UNITS_PER_METER as i128 / rhs.0 as i128,
+        ))
+    }
+}
+
+impl std::ops::Mul<i32> for Unit {
+    type Output = Self;
+
+    fn mul(self, rhs: i32) -> Self::Output {
+        Unit(self.0.saturating_mul(rhs))
+    }
+}
+
+impl std::ops::Div<i32> for Unit {
+    type Output = Self;
+
+    fn div(self, rhs: i32) -> Self::Output {
+        Unit(self.0.saturating_div(rhs))
+    }
+}
+
+impl std::ops::Neg for Unit {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Unit(self.0.saturating_neg())
     }
 }
 
 impl From<f32> for Unit {
     fn from(value: f32) -> Self {
-        Unit((value * 26.0) as i32)
+        Unit((value * UNITS_PER_METER as f32).round() as i32)
     }
 }
 
@@ -64,11 +143,11 @@ impl From<i32> for Unit {
 
 impl From<Unit> for f32 {
     fn from(value: Unit) -> Self {
-        value.0 as f32 / 26.0
+        value.0 as f32 / UNITS_PER_METER as f32
     }
 }
 
-#[derive(Default)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct UVec {
     pub x: Unit,
     pub y: Unit,
@@ -76,17 +155,151 @@ pub struct UVec {
 }
 
 impl UVec {
+    pub const ZERO: UVec = UVec::int(0, 0, 0);
+
     pub fn new(x: Unit, y: Unit, z: Unit) -> Self {
         UVec { x, y, z }
     }
-    pub fn int(x: i32, y: i32, z: i32) -> Self {
+
+    pub const fn int(x: i32, y: i32, z: i32) -> Self {
         UVec {
             x: Unit(x),
             y: Unit(y),
             z: Unit(z),
         }
     }
+
+    pub fn dot(self, rhs: UVec) -> Unit {
+        let raw = self.x.0 as i128 * rhs.x.0 as i128
+            + self.y.0 as i128 * rhs.y.0 as i128
+            + self.z.0 as i128 * rhs.z.0 as i128;
+        Unit(saturate(raw / UNITS_PER_METER as i128))
+    }
+
+    pub fn length(self) -> Unit {
+        let raw = self.x.0 as i128 * self.x.0 as i128
+            + self.y.0 as i128 * self.y.0 as i128
+            + self.z.0 as i128 * self.z.0 as i128;
+        Unit(saturate(isqrt(raw)))
+    }
+
+    pub fn along(direction: Vec3, length: Unit) -> Self {
+        UVec::new(
+            length.scale(direction.x),
+            length.scale(direction.y),
+            length.scale(direction.z),
+        )
+    }
+
+    pub fn project(self, direction: Vec3) -> Unit {
+        let raw = self.x.0 as f64 * direction.x as f64
+            + self.y.0 as f64 * direction.y as f64
+            + self.z.0 as f64 * direction.z as f64;
+        Unit(saturate(raw.round() as i128))
+    }
+
+    pub fn scale_to(self, length: Unit) -> Self {
+        let current = self.length();
+        if current == Unit::ZERO {
+            return UVec::ZERO;
+        }
+
+        let scale = |value: Unit| {
+            Unit(saturate(
+                value.0 as i128 * length.0 as i128 / current.0 as i128,
+            ))
+        };
+        UVec::new(scale(self.x), scale(self.y), scale(self.z))
+    }
+
+    pub fn div_rem(self, divisor: i32) -> (UVec, UVec) {
+        let (x, x_rem) = self.x.div_rem(divisor);
+        let (y, y_rem) = self.y.div_rem(divisor);
+        let (z, z_rem) = self.z.div_rem(divisor);
+        (UVec::new(x, y, z), UVec::new(x_rem, y_rem, z_rem))
+    }
+}
+
+impl std::ops::Add for UVec {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        UVec::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+    }
 }
+
+impl std::ops::AddAssign for UVec {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl std::ops::Sub for UVec {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        UVec::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+    }
+}
+
+impl std::ops::SubAssign for UVec {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl std::ops::Mul<Unit> for UVec {
+    type Output = Self;
+
+    fn mul(self, rhs: Unit) -> Self::Output {
+        UVec::new(self.x * rhs, self.y * rhs, self.z * rhs)
+    }
+}
+
+impl std::ops::Div<Unit> for UVec {
+    type Output = Self;
+
+    fn div(self, rhs: Unit) -> Self::Output {
+        UVec::new(self.x / rhs, self.y / rhs, self.z / rhs)
+    }
+}
+
+impl std::ops::Mul<i32> for UVec {
+    type Output = Self;
+
+    fn mul(self, rhs: i32) -> Self::Output {
+        UVec::new(self.x * rhs, self.y * rhs, self.z * rhs)
+    }
+}
+
+impl std::ops::Div<i32> for UVec {
+    type Output = Self;
+
+    fn div(self, rhs: i32) -> Self::Output {
+        UVec::new(self.x / rhs, self.y / rhs, self.z / rhs)
+    }
+}
+
+impl std::ops::Neg for UVec {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        UVec::new(-self.x, -self.y, -self.z)
+    }
+}
+
+impl From<Vec3> for UVec {
+    fn from(value: Vec3) -> Self {
+        UVec::new(value.x.into(), value.y.into(), value.z.into())
+    }
+}
+
+impl From<UVec> for Vec3 {
+    fn from(value: UVec) -> Self {
+        Vec3::new(value.x.into(), value.y.into(), value.z.into())
+    }
+}
+
 pub struct CorePlugin;
 
 impl Plugin for CorePlugin {
@@ -94,3 +307,113 @@ impl Plugin for CorePlugin {
         app.add_state::<GameState>();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mul_and_div_keep_the_unit_scale() {
+        let two = Unit::from(2.0);
+        let three = Unit::from(3.0);
+        assert_eq!(two * three, Unit::from(6.0));
+        assert_eq!(Unit::from(6.0) / three, two);
+        // results between units round toward zero, like integer division
+        assert_eq!(Unit::from(0.5) * Unit::from(0.5), Unit(6));
+        assert_eq!(-Unit::from(0.5) * Unit::from(0.5), Unit(-6));
+        assert_eq!(Unit(7) * 3, Unit(21));
+        assert_eq!(Unit(21) / 3, Unit(7));
+    }
+
+    #[test]
+    #[should_panic]
+    fn div_by_zero_panics() {
+        let _ = Unit::from(1.0) / Unit::ZERO;
+    }
+
+    #[test]
+    fn length_uses_the_integer_square_root() {
+        let vector = UVec::from(Vec3::new(3.0, 4.0, 0.0));
+        assert_eq!(vector.length(), Unit::from(5.0));
+        assert_eq!(UVec::ZERO.length(), Unit::ZERO);
+        assert_eq!(isqrt(0), 0);
+        assert_eq!(isqrt(1), 1);
+        assert_eq!(isqrt(15), 3);
+        assert_eq!(isqrt(16), 4);
+        assert_eq!(isqrt(i64::MAX as i128), 3_037_000_499);
+        assert_eq!(
+            UVec::int(i32::MIN, i32::MIN, i32::MIN).length(),
+            Unit(i32::MAX)
+        );
+    }
+
+    #[test]
+    fn dot_keeps_the_unit_scale() {
+        let x = UVec::from(Vec3::X * 2.0);
+        let xy = UVec::from(Vec3::new(3.0, 1.0, 0.0));
+        assert_eq!(x.dot(xy), Unit::from(6.0));
+        assert_eq!(x.dot(UVec::from(Vec3::Y)), Unit::ZERO);
+    }
+
+    #[test]
+    fn directions_scale_units_without_leaving_the_grid() {
+        assert_eq!(Unit(26).scale(0.5), Unit(13));
+        assert_eq!(Unit(-26).scale(0.5), Unit(-13));
+        assert_eq!(Unit(i32::MAX).scale(2.0), Unit(i32::MAX));
+        assert_eq!(Unit(10).scale(f32::NAN), Unit::ZERO);
+
+        let diagonal = Vec3::new(3.0, 0.0, 4.0) / 5.0;
+        let velocity = UVec::along(diagonal, Unit(50));
+        assert_eq!(velocity, UVec::int(30, 0, 40));
+        assert_eq!(velocity.project(diagonal), Unit(50));
+        assert_eq!(velocity.project(Vec3::Y), Unit::ZERO);
+        assert_eq!(velocity.scale_to(Unit(100)), UVec::int(60, 0, 80));
+        assert_eq!(UVec::ZERO.scale_to(Unit(100)), UVec::ZERO);
+    }
+
+    #[test]
+    fn div_rem_carries_the_remainder_without_drift() {
+        assert_eq!(Unit(61).div_rem(60), (Unit(1), Unit(1)));
+        assert_eq!(Unit(-61).div_rem(60), (Unit(-1), Unit(-1)));
+
+        let velocity = UVec::int(100, -7, 0);
+        let mut position = UVec::ZERO;
+        let mut carry = UVec::ZERO;
+        for _ in 0..60 {
+            let (step, remainder) = (velocity + carry).div_rem(60);
+            position += step;
+            carry = remainder;
+        }
+        assert_eq!(position, velocity);
+        assert_eq!(carry, UVec::ZERO);
+    }
+
+    #[test]
+    fn arithmetic_saturates_instead_of_overflowing() {
+        let max = Unit(i32::MAX);
+        let min = Unit(i32::MIN);
+        assert_eq!(max + Unit(1), max);
+        assert_eq!(min - Unit(1), min);
+        assert_eq!(max * 2, max);
+        assert_eq!(-min, max);
+        assert_eq!(max * max, max);
+        assert_eq!(max / Unit(1), max);
+        assert_eq!(min * Unit::from(2.0), min);
+        assert_eq!(min / -1, max);
+        assert_eq!(min.div_rem(-1), (max, Unit::ZERO));
+        assert_eq!(
+            UVec::int(i32::MAX, i32::MAX, 0).dot(UVec::int(i32::MAX, i32::MAX, 0)),
+            max
+        );
+    }
+
+    #[test]
+    fn float_conversion_rounds_to_the_nearest_unit() {
+        assert_eq!(Unit::from(1.0), Unit(UNITS_PER_METER));
+        assert_eq!(Unit::from(0.02), Unit(1));
+        assert_eq!(Unit::from(-0.02), Unit(-1));
+        assert_eq!(f32::from(Unit(13)), 0.5);
+        let roundtrip = UVec::int(5, -9, 130);
+        assert_eq!(UVec::from(Vec3::from(roundtrip)), roundtrip);
+    }
+}
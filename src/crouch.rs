@@ -0,0 +1,126 @@
+use bevy::ecs::query::Has;
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use crate::{
+    core::GameState,
+    input::{PlayerAction, TickActions},
+    physics::{Direction, Drift, FixedMotion, Gravity, Grounded, MotionSet, Speed},
+    player::{handle_jump, standing_collider, Player, PlayerState, LONG_JUMP_SPEED_FRACTION},
+};
+
+const CROUCH_SPEED_FACTOR: f32 = 0.25;
+const CROUCH_HEADROOM: f32 = 0.55;
+const BACKFLIP_VELOCITY: f32 = 20.0;
+const BACKFLIP_SPEED: f32 = 3.0;
+
+fn crouching_collider() -> Collider {
+    Collider::compound(vec![(
+        Vec3::NEG_Y * 0.25,
+        Quat::IDENTITY,
+        Collider::capsule_y(0.25, 0.5),
+    )])
+}
+
+type CrouchQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static mut Player,
+        &'static Transform,
+        &'static mut Collider,
+        &'static mut FixedMotion,
+        &'static mut Drift,
+        &'static mut Speed,
+        &'static Direction,
+        &'static Gravity,
+        &'static TickActions,
+        Has<Grounded>,
+    ),
+>;
+
+fn handle_crouch(
+    mut commands: Commands,
+    rapier_context: Res<RapierContext>,
+    mut player_query: CrouchQuery,
+) {
+    for (
+        entity,
+        mut player,
+        transform,
+        mut collider,
+        mut motion,
+        mut drift,
+        mut speed,
+        direction,
+        gravity,
+        action,
+        is_grounded,
+    ) in &mut player_query
+    {
+        match player.state {
+            PlayerState::Idle | PlayerState::Walking | PlayerState::Running
+                if is_grounded
+                    && action.pressed(PlayerAction::Crouch)
+                    && speed.fraction_of_max() < LONG_JUMP_SPEED_FRACTION =>
+            {
+                player.state = PlayerState::Crouching;
+                *collider = crouching_collider();
+                speed.scale_max(CROUCH_SPEED_FACTOR);
+            }
+            PlayerState::Crouching
+                if is_grounded
+                    && !direction.is_active()
+                    && action.just_pressed(PlayerAction::Jump) =>
+            {
+                player.state = PlayerState::Backflipping;
+                *collider = standing_collider();
+                speed.reset_max();
+                gravity.set_rise(&mut motion, BACKFLIP_VELOCITY.into());
+                drift.0 = -gravity.flatten(transform.forward()) * BACKFLIP_SPEED;
+                commands.entity(entity).remove::<Grounded>();
+            }
+            PlayerState::Crouching if !is_grounded || !action.pressed(PlayerAction::Crouch) => {
+                let filter = QueryFilter::default()
+                    .exclude_sensors()
+                    .exclude_collider(entity);
+                let is_blocked = rapier_context
+                    .cast_shape(
+                        transform.translation,
+                        transform.rotation,
+                        gravity.up,
+                        &Collider::ball(0.45),
+                        CROUCH_HEADROOM,
+                        filter,
+                    )
+                    .is_some();
+
+                if !is_blocked {
+                    player.state = if is_grounded {
+                        PlayerState::Idle
+                    } else {
+                        PlayerState::Freefall
+                    };
+                    *collider = standing_collider();
+                    speed.reset_max();
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+pub struct CrouchPlugin;
+
+impl Plugin for CrouchPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            handle_crouch
+                .after(handle_jump)
+                .in_set(MotionSet::React)
+                .run_if(in_state(GameState::Gameplay)),
+        );
+    }
+}
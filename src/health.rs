@@ -0,0 +1,205 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use crate::{
+    core::{GameState, UVec},
+    physics::{CharacterCollisionEvent, Drift, FixedMotion, Momentum, MotionSet},
+    player::{Player, PlayerState},
+};
+
+const INVULNERABILITY_SECONDS: f32 = 1.5;
+
+#[derive(Component)]
+pub struct Health {
+    current: u32,
+    max: u32,
+    invulnerability: Timer,
+}
+
+impl Health {
+    pub fn new(max: u32) -> Self {
+        let mut invulnerability = Timer::from_seconds(INVULNERABILITY_SECONDS, TimerMode::Once);
+        invulnerability.tick(invulnerability.duration());
+        Health {
+            current: max,
+            max,
+            invulnerability,
+        }
+    }
+
+    pub fn is_invulnerable(&self) -> bool {
+        !self.invulnerability.finished()
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.current == 0
+    }
+
+    pub fn hurt(&mut self, amount: u32) {
+        self.current = self.current.saturating_sub(amount);
+        self.invulnerability.reset();
+    }
+
+    pub fn restore(&mut self) {
+        self.current = self.max;
+    }
+}
+
+#[derive(Component)]
+pub struct SpawnPoint(pub Vec3);
+
+#[derive(Component)]
+pub struct Hazard {
+    pub damage: u32,
+}
+
+#[derive(Event)]
+pub struct DamageEvent {
+    pub entity: Entity,
+    pub source_position: Vec3,
+    pub amount: u32,
+    pub knockback: bool,
+}
+
+#[derive(Event)]
+pub struct RespawnEvent {
+    pub entity: Entity,
+}
+
+pub fn detect_hazards(
+    rapier_context: Res<RapierContext>,
+    mut collision_events: EventReader<CharacterCollisionEvent>,
+    mut damage_events: EventWriter<DamageEvent>,
+    hazard_query: Query<(&Hazard, &GlobalTransform)>,
+    character_query: Query<(Entity, &Transform, &Collider, &Health)>,
+) {
+    for event in collision_events.iter() {
+        let Ok((hazard, hazard_transform)) = hazard_query.get(event.other) else {
+            continue;
+        };
+        if character_query
+            .get(event.entity)
+            .is_ok_and(|(_, _, _, health)| !health.is_invulnerable())
+        {
+            damage_events.send(DamageEvent {
+                entity: event.entity,
+                source_position: hazard_transform.translation(),
+                amount: hazard.damage,
+                knockback: true,
+            });
+        }
+    }
+
+    // the character controller never reports sensors, so those are checked by overlap instead
+    for (entity, transform, collider, health) in &character_query {
+        if health.is_invulnerable() {
+            continue;
+        }
+
+        let is_hazard = |candidate| hazard_query.contains(candidate);
+        let filter = QueryFilter::default()
+            .exclude_solids()
+            .exclude_collider(entity)
+            .predicate(&is_hazard);
+
+        rapier_context.intersections_with_shape(
+            transform.translation,
+            transform.rotation,
+            collider,
+            filter,
+            |other| {
+                let (hazard, hazard_transform) = hazard_query.get(other).unwrap();
+                damage_events.send(DamageEvent {
+                    entity,
+                    source_position: hazard_transform.translation(),
+                    amount: hazard.damage,
+                    knockback: true,
+                });
+                false
+            },
+        );
+    }
+}
+
+fn tick_invulnerability(fixed_time: Res<FixedTime>, mut query: Query<&mut Health>) {
+    for mut health in &mut query {
+        if health.is_invulnerable() {
+            health.invulnerability.tick(fixed_time.period);
+        }
+    }
+}
+
+type DamageQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static mut Health,
+        &'static SpawnPoint,
+        &'static mut Transform,
+        &'static mut FixedMotion,
+        &'static mut Momentum,
+        &'static mut Drift,
+        Option<&'static mut Player>,
+    ),
+>;
+
+pub fn take_damage(
+    mut damage_events: EventReader<DamageEvent>,
+    mut respawn_events: EventWriter<RespawnEvent>,
+    mut query: DamageQuery,
+) {
+    for event in damage_events.iter() {
+        let Ok((
+            mut health,
+            spawn_point,
+            mut transform,
+            mut motion,
+            mut momentum,
+            mut drift,
+            player,
+        )) = query.get_mut(event.entity)
+        else {
+            continue;
+        };
+
+        if health.is_invulnerable() {
+            continue;
+        }
+
+        health.hurt(event.amount);
+        if !health.is_dead() {
+            continue;
+        }
+
+        health.restore();
+        motion.teleport(&mut transform, spawn_point.0);
+        motion.set_velocity(UVec::ZERO);
+        momentum.reset();
+        drift.0 = Vec3::ZERO;
+        if let Some(mut player) = player {
+            player.state = PlayerState::Idle;
+        }
+        respawn_events.send(RespawnEvent {
+            entity: event.entity,
+        });
+    }
+}
+
+pub struct HealthPlugin;
+
+impl Plugin for HealthPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<DamageEvent>()
+            .add_event::<RespawnEvent>()
+            .add_systems(
+                FixedUpdate,
+                (
+                    tick_invulnerability.before(detect_hazards),
+                    detect_hazards,
+                    take_damage.after(detect_hazards),
+                )
+                    .in_set(MotionSet::React)
+                    .run_if(in_state(GameState::Gameplay)),
+            );
+    }
+}
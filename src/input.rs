@@ -1,5 +1,7 @@
 use bevy::prelude::*;
-use leafwing_input_manager::{prelude::*, *};
+use leafwing_input_manager::{axislike::DualAxisData, plugin::InputManagerSystem, prelude::*, *};
+
+use crate::physics::MotionSet;
 
 #[derive(Actionlike, PartialEq, Eq, Clone, Copy, Hash, Debug, Default, Reflect)]
 pub enum PlayerAction {
@@ -7,15 +9,58 @@ pub enum PlayerAction {
     Jump,
     Move,
     Interact,
+    Crouch,
+    Dive,
+    Walk,
     CamRotateRight,
     CamRotateLeft,
     CamModeChangePositive,
     CamModeChangeNegative,
 }
 
+#[derive(Component, Default)]
+pub struct TickActions {
+    state: ActionState<PlayerAction>,
+    /// Presses held until a fixed tick has taken them
+    pending: Vec<PlayerAction>,
+    this_tick: Vec<PlayerAction>,
+}
+
+impl TickActions {
+    pub fn pressed(&self, action: PlayerAction) -> bool {
+        self.state.pressed(action)
+    }
+
+    pub fn just_pressed(&self, action: PlayerAction) -> bool {
+        self.this_tick.contains(&action)
+    }
+
+    pub fn clamped_axis_pair(&self, action: PlayerAction) -> Option<DualAxisData> {
+        self.state.clamped_axis_pair(action)
+    }
+}
+
+fn latch_actions(mut query: Query<(&ActionState<PlayerAction>, &mut TickActions)>) {
+    for (action_state, mut tick_actions) in &mut query {
+        for action in action_state.get_just_pressed() {
+            if !tick_actions.pending.contains(&action) {
+                tick_actions.pending.push(action);
+            }
+        }
+        tick_actions.state = action_state.clone();
+    }
+}
+
+pub fn advance_tick_actions(mut query: Query<&mut TickActions>) {
+    for mut tick_actions in &mut query {
+        tick_actions.this_tick = std::mem::take(&mut tick_actions.pending);
+    }
+}
+
 #[derive(Bundle)]
 pub struct InputListenerBundle {
     input_manager: InputManagerBundle<PlayerAction>,
+    tick_actions: TickActions,
 }
 
 impl InputListenerBundle {
@@ -25,6 +70,9 @@ impl InputListenerBundle {
         let input_map = input_map::InputMap::new([
             (KeyCode::Space, Jump),
             (KeyCode::L, Interact),
+            (KeyCode::ShiftLeft, Crouch),
+            (KeyCode::K, Dive),
+            (KeyCode::ControlLeft, Walk),
             (KeyCode::Left, CamRotateLeft),
             (KeyCode::Right, CamRotateRight),
             (KeyCode::Up, CamModeChangePositive),
@@ -33,6 +81,8 @@ impl InputListenerBundle {
         .insert_multiple([
             (GamepadButtonType::South, Jump),
             (GamepadButtonType::West, Interact),
+            (GamepadButtonType::LeftTrigger, Crouch),
+            (GamepadButtonType::East, Dive),
             (GamepadButtonType::LeftTrigger2, CamRotateLeft),
             (GamepadButtonType::RightTrigger2, CamRotateRight),
         ])
@@ -46,6 +96,7 @@ impl InputListenerBundle {
                 input_map,
                 ..Default::default()
             },
+            tick_actions: TickActions::default(),
         }
     }
 }
@@ -54,6 +105,11 @@ pub struct InputPlugin;
 
 impl Plugin for InputPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(InputManagerPlugin::<PlayerAction>::default());
+        app.add_plugins(InputManagerPlugin::<PlayerAction>::default())
+            .add_systems(
+                PreUpdate,
+                latch_actions.after(InputManagerSystem::ReleaseOnDisable),
+            )
+            .add_systems(FixedUpdate, advance_tick_actions.before(MotionSet::Move));
     }
 }
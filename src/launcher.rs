@@ -0,0 +1,161 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use crate::{
+    core::{GameState, UVec},
+    physics::{Carried, Drift, FixedMotion, Gravity, GroundContact, Grounded, Momentum, MotionSet},
+    player::{handle_grounded, LandingEvent, Player, PlayerMoves, PlayerState},
+};
+
+#[derive(Component)]
+pub struct Launcher {
+    pub launch: Launch,
+    /// Seconds of air control locked out after a launch
+    pub lockout: f32,
+}
+
+pub enum Launch {
+    Fixed(Vec3),
+    /// Multiplier on the incoming velocity reflected off the surface
+    Reflect(f32),
+    Trampoline {
+        base: f32,
+        multiplier: f32,
+    },
+}
+
+impl Launch {
+    fn velocity(&self, incoming: Vec3, normal: Vec3, up: Vec3) -> Vec3 {
+        match self {
+            Launch::Fixed(motion) => *motion,
+            Launch::Reflect(multiplier) => {
+                (incoming - normal * 2.0 * incoming.dot(normal)) * *multiplier
+            }
+            Launch::Trampoline { base, multiplier } => {
+                let fall_speed = (-incoming.dot(up)).max(0.0);
+                up * (*base + fall_speed * *multiplier)
+            }
+        }
+    }
+}
+
+fn tick_launch_lockout(fixed_time: Res<FixedTime>, mut player_query: Query<&mut PlayerMoves>) {
+    for mut moves in &mut player_query {
+        if let Some(lockout) = moves.launch_lockout.as_mut() {
+            lockout.tick(fixed_time.period);
+            if lockout.finished() {
+                moves.launch_lockout = None;
+            }
+        }
+    }
+}
+
+type LaunchQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static mut Player,
+        &'static Transform,
+        &'static Collider,
+        &'static mut FixedMotion,
+        &'static mut Momentum,
+        &'static mut Drift,
+        &'static mut Carried,
+        &'static GroundContact,
+        &'static Gravity,
+        &'static mut PlayerMoves,
+    ),
+>;
+
+fn handle_launchers(
+    mut commands: Commands,
+    rapier_context: Res<RapierContext>,
+    mut landing_events: EventReader<LandingEvent>,
+    launcher_query: Query<&Launcher>,
+    mut player_query: LaunchQuery,
+    mut overlapped_launcher: Local<Option<Entity>>,
+) {
+    let landings: Vec<_> = landing_events
+        .iter()
+        .filter(|event| launcher_query.contains(event.surface))
+        .map(|event| (event.entity, event.surface, event.impact_speed))
+        .collect();
+
+    for (
+        entity,
+        mut player,
+        transform,
+        collider,
+        mut motion,
+        mut momentum,
+        mut drift,
+        mut carried,
+        contact,
+        gravity,
+        mut moves,
+    ) in &mut player_query
+    {
+        let mut launch = None;
+
+        for (_, surface, impact_speed) in landings.iter().filter(|(lander, ..)| *lander == entity) {
+            let incoming = Vec3::from(motion.velocity()) - gravity.up * *impact_speed;
+            let launcher = launcher_query.get(*surface).unwrap();
+            launch = Some((launcher, incoming, contact.normal));
+        }
+
+        let is_launcher = |candidate| launcher_query.contains(candidate);
+        let mut sensor = None;
+        rapier_context.intersections_with_shape(
+            transform.translation,
+            transform.rotation,
+            collider,
+            QueryFilter::default()
+                .exclude_solids()
+                .exclude_collider(entity)
+                .predicate(&is_launcher),
+            |other| {
+                sensor = Some(other);
+                false
+            },
+        );
+
+        if let Some(other) = sensor.filter(|other| *overlapped_launcher != Some(*other)) {
+            let launcher = launcher_query.get(other).unwrap();
+            launch = Some((launcher, motion.velocity().into(), gravity.up));
+        }
+        *overlapped_launcher = sensor;
+
+        let Some((launcher, incoming, normal)) = launch else {
+            continue;
+        };
+
+        let launch_velocity = launcher.launch.velocity(incoming, normal, gravity.up);
+        let rise = launch_velocity.dot(gravity.up);
+        motion.set_velocity(UVec::along(gravity.up, rise.into()));
+        carried.0 = launch_velocity - gravity.up * rise;
+        momentum.reset();
+        drift.0 = Vec3::ZERO;
+
+        player.state = PlayerState::Rising;
+        moves.jump_stage = 0;
+        moves.launch_lockout = Some(Timer::from_seconds(launcher.lockout, TimerMode::Once));
+        commands.entity(entity).remove::<Grounded>();
+    }
+}
+
+pub struct LauncherPlugin;
+
+impl Plugin for LauncherPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            (
+                tick_launch_lockout.before(handle_launchers),
+                handle_launchers.after(handle_grounded),
+            )
+                .in_set(MotionSet::React)
+                .run_if(in_state(GameState::Gameplay)),
+        );
+    }
+}
@@ -0,0 +1,187 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use crate::{
+    camera::MainCamera,
+    core::{GameState, UVec},
+    input::{PlayerAction, TickActions},
+    physics::{Carried, Drift, FixedMotion, Gravity, Momentum, MotionSet},
+    player::{
+        get_direction_in_camera_space, handle_airborne_state, handle_ground_pound, Player,
+        PlayerMoves, PlayerState, PLAYER_GRAVITY_SCALE,
+    },
+};
+
+const HANG_DEPTH: f32 = 0.9;
+const HANG_DISTANCE: f32 = 0.55;
+const LEDGE_REACH: f32 = 0.8;
+const SHIMMY_SPEED: f32 = 2.5;
+
+#[derive(Clone, Copy)]
+pub struct Ledge {
+    pub point: Vec3,
+    pub normal: Vec3,
+}
+
+fn find_ledge(
+    rapier_context: &RapierContext,
+    entity: Entity,
+    translation: Vec3,
+    forward: Vec3,
+    up: Vec3,
+) -> Option<Ledge> {
+    let filter = QueryFilter::exclude_dynamic()
+        .exclude_sensors()
+        .exclude_collider(entity);
+
+    let chest = translation + up * 0.4;
+    let (_, wall_hit) =
+        rapier_context.cast_ray_and_get_normal(chest, forward, LEDGE_REACH, true, filter)?;
+    if wall_hit.normal.dot(up).abs() >= 0.3 {
+        return None;
+    }
+
+    let normal = (wall_hit.normal - up * wall_hit.normal.dot(up)).normalize();
+    let above_lip = wall_hit.point - normal * 0.1 + up * 1.1;
+    let (_, top_hit) = rapier_context.cast_ray_and_get_normal(above_lip, -up, 1.1, true, filter)?;
+    if top_hit.normal.dot(up) <= 0.7 {
+        return None;
+    }
+
+    Some(Ledge {
+        point: wall_hit.point + up * (top_hit.point - wall_hit.point).dot(up),
+        normal,
+    })
+}
+
+fn hang_from(ledge: &Ledge, up: Vec3) -> Vec3 {
+    ledge.point + ledge.normal * HANG_DISTANCE - up * HANG_DEPTH
+}
+
+type LedgeQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static mut Player,
+        &'static mut Transform,
+        &'static mut FixedMotion,
+        &'static mut Momentum,
+        &'static mut Drift,
+        &'static mut Carried,
+        &'static mut Gravity,
+        &'static mut PlayerMoves,
+        &'static TickActions,
+    ),
+>;
+
+pub fn handle_ledges(
+    fixed_time: Res<FixedTime>,
+    rapier_context: Res<RapierContext>,
+    mut player_query: LedgeQuery,
+    camera_query: Query<&Transform, (With<MainCamera>, Without<Player>)>,
+) {
+    let camera_transform = camera_query.single();
+    for (
+        entity,
+        mut player,
+        mut transform,
+        mut motion,
+        mut momentum,
+        mut drift,
+        mut carried,
+        mut gravity,
+        mut moves,
+        action,
+    ) in &mut player_query
+    {
+        moves.ledge_cooldown.tick(fixed_time.period);
+
+        let flat_forward = gravity.flatten(transform.forward());
+
+        match player.state {
+            PlayerState::Freefall if moves.ledge_cooldown.finished() => {
+                if let Some(ledge) = find_ledge(
+                    &rapier_context,
+                    entity,
+                    transform.translation,
+                    flat_forward,
+                    gravity.up,
+                ) {
+                    player.state = PlayerState::Hanging;
+                    motion.teleport(&mut transform, hang_from(&ledge, gravity.up));
+                    transform.look_to(-ledge.normal, gravity.up);
+                    motion.set_velocity(UVec::ZERO);
+                    momentum.reset();
+                    drift.0 = Vec3::ZERO;
+                    carried.0 = Vec3::ZERO;
+                    gravity.scale = 0.0;
+                    moves.ledge = Some(ledge);
+                }
+            }
+            PlayerState::Hanging => {
+                let Some(ledge) = moves.ledge else {
+                    continue;
+                };
+
+                if action.just_pressed(PlayerAction::Jump) {
+                    player.state = PlayerState::Idle;
+                    let top = ledge.point - ledge.normal * 0.6 + gravity.up * 1.05;
+                    motion.teleport(&mut transform, top);
+                    motion.set_velocity(UVec::ZERO);
+                    gravity.scale = PLAYER_GRAVITY_SCALE;
+                    moves.ledge = None;
+                } else if action.just_pressed(PlayerAction::Crouch) {
+                    player.state = PlayerState::Freefall;
+                    motion.set_velocity(ledge.normal.into());
+                    gravity.scale = PLAYER_GRAVITY_SCALE;
+                    moves.ledge = None;
+                    moves.ledge_cooldown = Timer::from_seconds(0.3, TimerMode::Once);
+                } else {
+                    let input = get_direction_in_camera_space(camera_transform, &gravity, action);
+                    let along_edge = ledge.normal.cross(gravity.up).normalize();
+                    let shimmy = input.dot(along_edge);
+                    motion.set_velocity(UVec::ZERO);
+
+                    if shimmy.abs() >= 0.3 {
+                        let step = along_edge * shimmy.signum() * HANG_DISTANCE;
+                        let edge_continues = find_ledge(
+                            &rapier_context,
+                            entity,
+                            transform.translation + step,
+                            -ledge.normal,
+                            gravity.up,
+                        )
+                        .is_some_and(|next| (next.point - ledge.point).dot(gravity.up).abs() < 0.2);
+
+                        if edge_continues {
+                            motion.set_velocity((along_edge * shimmy * SHIMMY_SPEED).into());
+                            moves.ledge = Some(Ledge {
+                                point: transform.translation
+                                    + ledge.normal * -HANG_DISTANCE
+                                    + gravity.up * HANG_DEPTH,
+                                ..ledge
+                            });
+                        }
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+pub struct LedgePlugin;
+
+impl Plugin for LedgePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            handle_ledges
+                .after(handle_airborne_state)
+                .after(handle_ground_pound)
+                .in_set(MotionSet::React)
+                .run_if(in_state(GameState::Gameplay)),
+        );
+    }
+}
@@ -1,8 +1,35 @@
+use bevy::ecs::system::EntityCommands;
 use bevy::prelude::*;
 use bevy_rapier3d::prelude::*;
 
 use crate::assets::MaterialCache;
+use crate::carry::Carryable;
 use crate::core::GameState;
+use crate::health::Hazard;
+use crate::launcher::{Launch, Launcher};
+use crate::physics::{GravityVolume, SlideSurface, SurfaceType, WindVolume};
+use crate::platform::{PlatformPath, PlatformSpin, PlatformVelocity};
+use crate::water::WaterVolume;
+use crate::{bump::BumpReaction, pound::PoundReaction};
+
+fn spawn_block<'w, 's, 'a>(
+    commands: &'a mut Commands<'w, 's>,
+    meshes: &mut Assets<Mesh>,
+    materials: &MaterialCache,
+    transform: Transform,
+    size: Vec3,
+) -> EntityCommands<'w, 's, 'a> {
+    let mut block = commands.spawn(PbrBundle {
+        mesh: meshes.add(Mesh::from(shape::Box::new(size.x, size.y, size.z))),
+        material: materials.checkerboard.clone_weak(),
+        transform,
+        ..default()
+    });
+    block
+        .insert(Collider::cuboid(size.x / 2.0, size.y / 2.0, size.z / 2.0))
+        .insert(RigidBody::Fixed);
+    block
+}
 
 pub fn spawn_level(
     mut commands: Commands,
@@ -10,15 +37,276 @@ pub fn spawn_level(
     materials: Res<MaterialCache>,
 ) {
     commands.spawn(DirectionalLightBundle::default());
+    spawn_block(
+        &mut commands,
+        &mut meshes,
+        &materials,
+        Transform::from_translation(Vec3::NEG_Y * 1.0),
+        Vec3::new(10.0, 0.5, 10.0),
+    );
+
+    spawn_block(
+        &mut commands,
+        &mut meshes,
+        &materials,
+        Transform::from_xyz(0.0, 0.5, 8.5).with_rotation(Quat::from_rotation_x(0.3)),
+        Vec3::new(4.0, 0.5, 8.0),
+    );
+
+    spawn_block(
+        &mut commands,
+        &mut meshes,
+        &materials,
+        Transform::from_xyz(8.5, 1.5, 0.0).with_rotation(Quat::from_rotation_z(0.6)),
+        Vec3::new(4.0, 0.5, 8.0),
+    )
+    .insert(SlideSurface);
+
+    for (position, reaction) in [
+        (
+            Vec3::new(-3.5, -0.6, 3.5),
+            PoundReaction::Switch { pressed: false },
+        ),
+        (Vec3::new(3.5, -0.6, 3.5), PoundReaction::Breakable),
+        (Vec3::new(0.0, -0.6, -4.0), PoundReaction::Bounce(18.0)),
+    ] {
+        spawn_block(
+            &mut commands,
+            &mut meshes,
+            &materials,
+            Transform::from_translation(position),
+            Vec3::new(1.5, 0.3, 1.5),
+        )
+        .insert(reaction);
+    }
+
+    spawn_block(
+        &mut commands,
+        &mut meshes,
+        &materials,
+        Transform::from_xyz(-7.0, -0.5, 0.0),
+        Vec3::new(3.0, 0.5, 3.0),
+    )
+    .insert(RigidBody::KinematicPositionBased)
+    .insert(PlatformVelocity::default())
+    .insert(PlatformPath::new(
+        vec![
+            Vec3::new(-7.0, -0.5, 0.0),
+            Vec3::new(-7.0, 2.5, -6.0),
+            Vec3::new(-13.0, 2.5, -6.0),
+        ],
+        2.0,
+    ));
+
+    spawn_block(
+        &mut commands,
+        &mut meshes,
+        &materials,
+        Transform::from_xyz(0.0, -0.5, -9.0),
+        Vec3::new(5.0, 0.5, 5.0),
+    )
+    .insert(RigidBody::KinematicPositionBased)
+    .insert(PlatformVelocity::default())
+    .insert(PlatformSpin(Vec3::Y * 0.8));
+
+    for (position, surface) in [
+        (Vec3::new(-2.5, 0.5, 15.5), SurfaceType::Ice),
+        (Vec3::new(2.5, 0.5, 15.5), SurfaceType::Sand),
+        (Vec3::new(-2.5, 0.5, 20.5), SurfaceType::Mud),
+        (
+            Vec3::new(2.5, 0.5, 20.5),
+            SurfaceType::Conveyor(Vec3::new(0.0, 0.0, -4.0)),
+        ),
+        (Vec3::new(0.0, 0.5, 25.5), SurfaceType::Bouncy),
+    ] {
+        spawn_block(
+            &mut commands,
+            &mut meshes,
+            &materials,
+            Transform::from_translation(position),
+            Vec3::new(5.0, 0.5, 5.0),
+        )
+        .insert(surface);
+    }
+
+    commands
+        .spawn(TransformBundle::from_transform(Transform::from_xyz(
+            -8.0, 3.0, 8.0,
+        )))
+        .insert(Collider::cuboid(3.0, 4.0, 3.0))
+        .insert(Sensor)
+        .insert(GravityVolume::Low(0.3));
+
+    commands
+        .spawn(TransformBundle::from_transform(Transform::from_xyz(
+            8.0, 3.5, 8.0,
+        )))
+        .insert(Collider::cuboid(2.0, 4.0, 2.0))
+        .insert(Sensor)
+        .insert(GravityVolume::Reversed);
+
+    for (position, half_extents, wind) in [
+        (
+            Vec3::new(-2.5, 2.0, 15.5),
+            Vec3::new(2.5, 1.5, 2.5),
+            WindVolume::Constant(Vec3::new(8.0, 0.0, 0.0)),
+        ),
+        (
+            Vec3::new(0.0, 2.0, 25.5),
+            Vec3::new(2.5, 1.5, 2.5),
+            WindVolume::Turbulent {
+                force: Vec3::new(-6.0, 0.0, 0.0),
+                gust: 0.6,
+            },
+        ),
+        (
+            Vec3::new(-3.5, 3.5, 7.0),
+            Vec3::new(1.0, 4.0, 1.0),
+            WindVolume::Updraft(40.0),
+        ),
+    ] {
+        commands
+            .spawn(TransformBundle::from_transform(
+                Transform::from_translation(position),
+            ))
+            .insert(Collider::cuboid(
+                half_extents.x,
+                half_extents.y,
+                half_extents.z,
+            ))
+            .insert(Sensor)
+            .insert(wind);
+    }
+    spawn_block(
+        &mut commands,
+        &mut meshes,
+        &materials,
+        Transform::from_xyz(8.0, 7.25, 8.0),
+        Vec3::new(4.0, 0.5, 4.0),
+    );
+
+    let planetoid_position = Vec3::new(0.0, 6.0, -20.0);
     commands
         .spawn(PbrBundle {
-            mesh: meshes.add(Mesh::from(shape::Box::new(10.0, 0.5, 10.0))),
+            mesh: meshes.add(Mesh::from(shape::UVSphere {
+                radius: 3.0,
+                ..default()
+            })),
             material: materials.checkerboard.clone_weak(),
-            transform: Transform::from_translation(Vec3::NEG_Y * 1.0),
+            transform: Transform::from_translation(planetoid_position),
             ..default()
         })
-        .insert(Collider::cuboid(5.0, 0.25, 5.0))
+        .insert(Collider::ball(3.0))
         .insert(RigidBody::Fixed);
+    commands
+        .spawn(TransformBundle::from_transform(
+            Transform::from_translation(planetoid_position),
+        ))
+        .insert(Collider::ball(7.0))
+        .insert(Sensor)
+        .insert(GravityVolume::Spherical);
+
+    spawn_block(
+        &mut commands,
+        &mut meshes,
+        &materials,
+        Transform::from_xyz(4.0, -0.5, -3.0),
+        Vec3::new(1.0, 0.5, 1.0),
+    )
+    .insert(Hazard { damage: 1 });
+
+    for (position, launcher) in [
+        (
+            Vec3::new(-4.0, -0.6, -1.0),
+            Launcher {
+                launch: Launch::Fixed(Vec3::new(0.0, 18.0, -8.0)),
+                lockout: 0.8,
+            },
+        ),
+        (
+            Vec3::new(-4.0, -0.6, 1.0),
+            Launcher {
+                launch: Launch::Reflect(1.2),
+                lockout: 0.3,
+            },
+        ),
+        (
+            Vec3::new(4.0, -0.6, 0.0),
+            Launcher {
+                launch: Launch::Trampoline {
+                    base: 10.0,
+                    multiplier: 0.9,
+                },
+                lockout: 0.2,
+            },
+        ),
+    ] {
+        spawn_block(
+            &mut commands,
+            &mut meshes,
+            &materials,
+            Transform::from_translation(position),
+            Vec3::new(1.5, 0.3, 1.5),
+        )
+        .insert(launcher);
+    }
+
+    for (position, reaction) in [
+        (Vec3::new(5.5, 3.0, -5.0), BumpReaction::Breakable),
+        (
+            Vec3::new(7.0, 3.0, -5.0),
+            BumpReaction::Release { released: false },
+        ),
+        (Vec3::new(8.5, 3.0, -5.0), BumpReaction::Bounce(6.0)),
+    ] {
+        spawn_block(
+            &mut commands,
+            &mut meshes,
+            &materials,
+            Transform::from_translation(position),
+            Vec3::splat(1.0),
+        )
+        .insert(reaction);
+    }
+
+    for (position, size) in [
+        (Vec3::new(9.5, -5.0, 0.0), Vec3::new(9.0, 0.5, 8.0)),
+        (Vec3::new(4.75, -3.0, 0.0), Vec3::new(0.5, 3.5, 8.0)),
+        (Vec3::new(14.25, -2.875, 0.0), Vec3::new(0.5, 4.25, 9.0)),
+        (Vec3::new(9.5, -2.875, -4.25), Vec3::new(10.0, 4.25, 0.5)),
+        (Vec3::new(9.5, -2.875, 4.25), Vec3::new(10.0, 4.25, 0.5)),
+    ] {
+        spawn_block(
+            &mut commands,
+            &mut meshes,
+            &materials,
+            Transform::from_translation(position),
+            size,
+        );
+    }
+
+    commands
+        .spawn(TransformBundle::from_transform(Transform::from_xyz(
+            9.5, -2.875, 0.0,
+        )))
+        .insert(Collider::cuboid(4.5, 1.875, 4.0))
+        .insert(Sensor)
+        .insert(WaterVolume {
+            surface: Vec3::new(9.5, -1.0, 0.0),
+        });
+
+    for position in [Vec3::new(2.0, 0.0, -2.0), Vec3::new(-2.0, 0.0, -3.0)] {
+        commands
+            .spawn(PbrBundle {
+                mesh: meshes.add(Mesh::from(shape::Cube::new(0.5))),
+                material: materials.checkerboard.clone_weak(),
+                transform: Transform::from_translation(position),
+                ..default()
+            })
+            .insert(Collider::cuboid(0.25, 0.25, 0.25))
+            .insert(RigidBody::Dynamic)
+            .insert(Carryable);
+    }
 }
 
 pub struct LevelPlugin;
@@ -1,34 +1,65 @@
+use bevy::asset::ChangeWatcher;
 use bevy::prelude::*;
 use bevy_inspector_egui::quick::WorldInspectorPlugin;
 use bevy_rapier3d::prelude::*;
+use std::time::Duration;
 
 mod animation;
 mod assets;
+mod bump;
 mod camera;
+mod carry;
 mod core;
+mod crouch;
+mod health;
 mod input;
+mod launcher;
+mod ledge;
 mod level;
 mod particles;
 mod physics;
+mod platform;
 mod player;
+mod pound;
+mod water;
 
 fn main() {
     App::new()
         .add_plugins((
-            DefaultPlugins.set(ImagePlugin::default_nearest()),
+            DefaultPlugins
+                .set(ImagePlugin::default_nearest())
+                .set(AssetPlugin {
+                    watch_for_changes: if cfg!(feature = "hot_reload") {
+                        ChangeWatcher::with_delay(Duration::from_millis(200))
+                    } else {
+                        None
+                    },
+                    ..default()
+                }),
             WorldInspectorPlugin::default(),
             RapierPhysicsPlugin::<NoUserData>::default(),
         ))
         .add_plugins((
             core::CorePlugin,
             camera::CameraPlugin,
+            carry::CarryPlugin,
+            health::HealthPlugin,
             assets::AssetPlugin,
             level::LevelPlugin,
+            launcher::LauncherPlugin,
             physics::PhysicsPlugin,
+            platform::PlatformPlugin,
             player::PlayerPlugin,
             animation::AnimationPlugin,
             input::InputPlugin,
             particles::ParticlePlugin,
+            water::WaterPlugin,
+        ))
+        .add_plugins((
+            pound::PoundPlugin,
+            bump::BumpPlugin,
+            ledge::LedgePlugin,
+            crouch::CrouchPlugin,
         ))
         .run();
 }
@@ -6,6 +6,8 @@ pub struct ParticlePlugin;
 #[derive(Resource)]
 pub struct ParticleCache {
     pub dust: Handle<EffectAsset>,
+    pub dust_ring: Handle<EffectAsset>,
+    pub splash: Handle<EffectAsset>,
 }
 
 #[derive(Component, Default)]
@@ -138,7 +140,84 @@ fn initialize_particle_cache(
     // Insert into the asset system
     let effect_handle = effects.add(effect);
 
+    // A flat ring of dust thrown outward along the ground, used for heavy impacts
+    let mut ring_gradient = Gradient::new();
+    ring_gradient.add_key(0.0, Vec4::splat(1.));
+    ring_gradient.add_key(1.0, Vec4::splat(0.));
+
+    let mut ring_module = Module::default();
+
+    let ring_pos = SetPositionCircleModifier {
+        center: ring_module.lit(Vec3::ZERO),
+        axis: ring_module.lit(Vec3::Y),
+        radius: ring_module.lit(0.3),
+        dimension: ShapeDimension::Surface,
+    };
+
+    let ring_vel = SetVelocityCircleModifier {
+        center: ring_module.lit(Vec3::ZERO),
+        axis: ring_module.lit(Vec3::Y),
+        speed: ring_module.lit(3.0),
+    };
+
+    let ring_lifetime = SetAttributeModifier::new(Attribute::LIFETIME, ring_module.lit(0.4));
+    let ring_size = SetAttributeModifier::new(Attribute::SIZE, ring_module.lit(0.4));
+
+    let ring_effect = EffectAsset::new(12, Spawner::once(12.0.into(), true), ring_module)
+        .with_name("DustRing")
+        .init(ring_pos)
+        .init(ring_vel)
+        .init(ring_size)
+        .init(ring_lifetime)
+        .render(ParticleTextureModifier {
+            texture: assets.load("textures/dust.png"),
+        })
+        .render(BillboardModifier)
+        .render(ColorOverLifetimeModifier {
+            gradient: ring_gradient,
+        });
+
+    // Droplets thrown up and out of the water surface, falling back under gravity
+    let mut splash_gradient = Gradient::new();
+    splash_gradient.add_key(0.0, Vec4::new(0.8, 0.9, 1.0, 1.0));
+    splash_gradient.add_key(1.0, Vec4::new(0.8, 0.9, 1.0, 0.0));
+
+    let mut splash_module = Module::default();
+
+    let splash_pos = SetPositionCircleModifier {
+        center: splash_module.lit(Vec3::ZERO),
+        axis: splash_module.lit(Vec3::Y),
+        radius: splash_module.lit(0.4),
+        dimension: ShapeDimension::Volume,
+    };
+
+    let splash_vel = SetVelocitySphereModifier {
+        center: splash_module.lit(Vec3::NEG_Y),
+        speed: splash_module.lit(4.0),
+    };
+
+    let splash_lifetime = SetAttributeModifier::new(Attribute::LIFETIME, splash_module.lit(0.6));
+    let splash_size = SetAttributeModifier::new(Attribute::SIZE, splash_module.lit(0.25));
+    let splash_accel = AccelModifier::new(splash_module.lit(Vec3::new(0., -9.8, 0.)));
+
+    let splash_effect = EffectAsset::new(16, Spawner::once(16.0.into(), true), splash_module)
+        .with_name("Splash")
+        .init(splash_pos)
+        .init(splash_vel)
+        .init(splash_size)
+        .init(splash_lifetime)
+        .update(splash_accel)
+        .render(ParticleTextureModifier {
+            texture: assets.load("textures/dust.png"),
+        })
+        .render(BillboardModifier)
+        .render(ColorOverLifetimeModifier {
+            gradient: splash_gradient,
+        });
+
     commands.insert_resource(ParticleCache {
         dust: effect_handle,
+        dust_ring: effects.add(ring_effect),
+        splash: effects.add(splash_effect),
     });
 }
@@ -1,73 +1,257 @@
-use crate::core::{Character, GameState, Unit};
+use crate::core::{Character, GameState, UVec, Unit};
+use bevy::ecs::query::Has;
+use bevy::ecs::schedule::ExecutorKind;
 use bevy::prelude::*;
+use bevy::reflect::{TypePath, TypeUuid};
+use bevy::utils::HashSet;
 use bevy_rapier3d::prelude::*;
+use serde::Deserialize;
+use std::time::Duration;
 
-#[derive(Default, Component)]
-pub struct Direction(pub Vec3);
+const GROUND_NORMAL_Y: f32 = 0.7;
+const UPRIGHT_SPEED: f32 = 8.0;
+const WIND_DRAG: f32 = 2.0;
+const WIND_SETTLE_SPEED: f32 = 0.05;
+
+#[derive(Deserialize, TypeUuid, TypePath)]
+#[uuid = "d227684d-867b-4e0e-bb2e-c5f8cd7a0995"]
+pub struct MovementProfile {
+    pub base_speed: f32,
+    pub max_speed: f32,
+    pub acceleration: f32,
+    pub acceleration_delay: f32,
+    pub reset_delay: f32,
+    pub damping: f32,
+    pub turn_factor: f32,
+    pub deadzone: f32,
+    pub walk_speed: f32,
+    pub run_threshold: f32,
+}
+
+impl MovementProfile {
+    fn invalid_field(&self) -> Option<&'static str> {
+        let fields = [
+            ("base_speed", self.base_speed),
+            ("max_speed", self.max_speed),
+            ("acceleration", self.acceleration),
+            ("acceleration_delay", self.acceleration_delay),
+            ("reset_delay", self.reset_delay),
+            ("damping", self.damping),
+            ("turn_factor", self.turn_factor),
+            ("deadzone", self.deadzone),
+            ("walk_speed", self.walk_speed),
+            ("run_threshold", self.run_threshold),
+        ];
+        let delays = [
+            ("acceleration_delay", self.acceleration_delay),
+            ("reset_delay", self.reset_delay),
+        ];
+
+        fields
+            .into_iter()
+            .find(|(_, value)| !value.is_finite() || *value < 0.0)
+            .or_else(|| {
+                delays
+                    .into_iter()
+                    .find(|(_, delay)| Duration::try_from_secs_f32(*delay).is_err())
+            })
+            .map(|(name, _)| name)
+    }
+}
+
+#[derive(Component)]
+pub struct Direction {
+    value: Vec3,
+    deadzone: f32,
+    run_threshold: f32,
+}
 
 impl Direction {
     pub fn get(&self) -> Vec3 {
-        self.0
+        self.value
     }
 
     pub fn set(&mut self, value: Vec3) {
-        self.0 = value;
+        self.value = value;
     }
 
     pub fn is_any(&self) -> bool {
-        self.0 != Vec3::ZERO
+        self.value != Vec3::ZERO
     }
 
     pub fn is_active(&self) -> bool {
-        self.0.length() >= 0.3
+        self.value.length() >= self.deadzone
+    }
+
+    pub fn is_full_tilt(&self) -> bool {
+        self.value.length() >= self.run_threshold
+    }
+}
+
+impl Default for Direction {
+    fn default() -> Self {
+        Direction {
+            value: Vec3::ZERO,
+            deadzone: 0.3,
+            run_threshold: 0.7,
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct Gravity {
+    pub up: Vec3,
+    pub strength: f32,
+    pub scale: f32,
+}
+
+impl Gravity {
+    pub fn rise(&self, motion: &FixedMotion) -> Unit {
+        motion.velocity.project(self.up)
+    }
+
+    pub fn set_rise(&self, motion: &mut FixedMotion, speed: Unit) {
+        let rise = self.rise(motion);
+        motion.velocity += UVec::along(self.up, speed - rise);
+    }
+
+    pub fn flatten(&self, direction: Vec3) -> Vec3 {
+        (direction - self.up * direction.dot(self.up)).normalize_or_zero()
+    }
+}
+
+impl Default for Gravity {
+    fn default() -> Self {
+        Gravity {
+            up: Vec3::Y,
+            strength: 1.0,
+            scale: 1.0,
+        }
     }
 }
 
+#[derive(Component)]
+pub enum GravityVolume {
+    Low(f32),
+    Reversed,
+    Spherical,
+}
+
+#[derive(Component)]
+pub enum WindVolume {
+    Constant(Vec3),
+    Turbulent { force: Vec3, gust: f32 },
+    Updraft(f32),
+}
+
+#[derive(Default, Component)]
+pub struct Push {
+    pub conveyor: Vec3,
+    pub wind: Vec3,
+    pub blown: Vec3,
+}
+
+#[derive(Component)]
+pub struct HoldTilt;
+
 #[derive(Default, Component)]
 pub struct Drift(pub Vec3);
 
+#[derive(Default, Component)]
+pub struct Carried(pub Vec3);
+
+#[derive(Component)]
+pub struct LinearDamping(pub f32);
+
 #[derive(Component)]
 pub struct Speed {
-    current: f32,
+    current: Unit,
     accel: f32,
-    base: f32,
-    max: f32,
-    base_max: f32,
+    base: Unit,
+    max: Unit,
+    base_max: Unit,
+    walk: Unit,
+    turn_factor: f32,
     accel_timer: Timer,
     reset_timer: Timer,
 }
 
 impl Speed {
+    pub fn fraction_of_max(&self) -> f32 {
+        self.current.0 as f32 / self.base_max.0 as f32
+    }
+
     pub fn reset(&mut self) {
         self.current = self.base;
-        self.max = self.base_max;
         self.accel_timer.reset();
     }
 
+    pub fn scale_max(&mut self, factor: f32) {
+        self.max = self.base_max.scale(factor);
+        self.cap();
+    }
+
+    pub fn reset_max(&mut self) {
+        self.max = self.base_max;
+    }
+
+    pub fn limit(&mut self, factor: f32) {
+        self.current = self.current.min(self.base_max.scale(factor));
+    }
+
+    pub fn limit_to_walk(&mut self) {
+        self.current = self.current.min(self.walk);
+    }
+
     pub fn tick_reset_timer(&mut self, delta: std::time::Duration) {
         self.reset_timer.tick(delta);
     }
 
     pub fn reset_reset_timer(&mut self) {
-        self.reset_timer = Timer::from_seconds(0.25, TimerMode::Once);
+        self.reset_timer.reset();
+    }
+
+    pub fn turn_speed(&self) -> f32 {
+        f32::from(self.current) * self.turn_factor
+    }
+
+    pub fn retune(&mut self, profile: &MovementProfile) {
+        self.base = profile.base_speed.into();
+        self.accel = profile.acceleration;
+        let max_factor = if self.base_max > Unit::ZERO {
+            self.max.0 as f32 / self.base_max.0 as f32
+        } else {
+            1.0
+        };
+        self.base_max = profile.max_speed.into();
+        self.max = self.base_max.scale(max_factor);
+        self.walk = profile.walk_speed.into();
+        self.turn_factor = profile.turn_factor;
+        self.accel_timer
+            .set_duration(Duration::from_secs_f32(profile.acceleration_delay));
+        self.reset_timer
+            .set_duration(Duration::from_secs_f32(profile.reset_delay));
+        self.cap();
     }
 
     pub fn should_reset(&self) -> bool {
         self.reset_timer.finished()
     }
 
-    pub fn apply_speed(&mut self, value: f32) {
+    pub fn current(&self) -> Unit {
+        self.current
+    }
+
+    pub fn apply_speed(&mut self, value: Unit) {
         self.current += value;
         self.cap();
     }
 
     pub fn cap(&mut self) {
-        if self.current > 0.0 {
-            if self.current >= self.max {
-                self.current = self.max;
-            }
+        if self.current > Unit::ZERO {
+            self.current = self.current.min(self.max);
         } else {
-            self.current = self.current * 0.5;
+            self.current = self.current / 2;
         }
     }
 
@@ -75,7 +259,10 @@ impl Speed {
         self.accel_timer.tick(delta);
         if self.accel_timer.finished() {
             if self.current < self.max {
-                self.current = self.current + (self.max - self.current) * (seconds * self.accel);
+                let gain = (self.max - self.current)
+                    .scale(seconds * self.accel)
+                    .max(Unit(1));
+                self.current = (self.current + gain).min(self.max);
             } else {
                 self.current = self.max;
             }
@@ -86,11 +273,13 @@ impl Speed {
 impl Default for Speed {
     fn default() -> Self {
         Speed {
-            base: 3.0,
-            current: 3.0,
+            base: Unit::from(3.0),
+            current: Unit::from(3.0),
             accel: 2.5,
-            max: 24.0,
-            base_max: 24.0,
+            max: Unit::from(24.0),
+            base_max: Unit::from(24.0),
+            walk: Unit::from(6.0),
+            turn_factor: 0.85,
             accel_timer: Timer::from_seconds(0.6, TimerMode::Once),
             reset_timer: Timer::from_seconds(0.25, TimerMode::Once),
         }
@@ -98,136 +287,353 @@ impl Default for Speed {
 }
 
 #[derive(Default, Component)]
-pub struct Momentum(f32);
+pub struct Momentum(Unit);
 
 impl Momentum {
-    pub fn get(&self) -> f32 {
+    pub fn get(&self) -> Unit {
         self.0
     }
 
-    pub fn set(&mut self, value: f32) {
+    pub fn set(&mut self, value: Unit) {
         self.0 = value;
     }
 
     pub fn is_any(&self) -> bool {
-        self.0 != 0.0
+        self.0 != Unit::ZERO
     }
 
     pub fn reset(&mut self) {
-        self.0 = 0.0;
+        self.0 = Unit::ZERO;
     }
 }
+
 #[derive(Component)]
 pub struct Grounded;
 
+#[derive(Component)]
+pub struct GroundProbe {
+    pub radius: f32,
+    pub reach: f32,
+    pub snap_distance: f32,
+}
+
+impl Default for GroundProbe {
+    fn default() -> Self {
+        GroundProbe {
+            radius: 0.4,
+            reach: 1.0,
+            snap_distance: 0.3,
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct GroundContact {
+    pub entity: Option<Entity>,
+    pub point: Vec3,
+    pub normal: Vec3,
+}
+
+impl Default for GroundContact {
+    fn default() -> Self {
+        GroundContact {
+            entity: None,
+            point: Vec3::ZERO,
+            normal: Vec3::Y,
+        }
+    }
+}
+
+#[derive(Default, Component)]
+pub struct FixedMotion {
+    position: UVec,
+    velocity: UVec,
+    grounded: bool,
+    position_carry: UVec,
+    velocity_carry: UVec,
+}
+
+impl FixedMotion {
+    pub fn velocity(&self) -> UVec {
+        self.velocity
+    }
+
+    pub fn set_velocity(&mut self, velocity: UVec) {
+        self.velocity = velocity;
+    }
+
+    pub fn teleport(&mut self, transform: &mut Transform, position: Vec3) {
+        self.position = position.into();
+        self.position_carry = UVec::ZERO;
+        transform.translation = self.position.into();
+    }
+
+    fn plan_step(
+        &mut self,
+        up: Vec3,
+        pull: UVec,
+        damping: Option<i32>,
+        carried: UVec,
+        tick_rate: i32,
+    ) -> (UVec, UVec) {
+        let mut velocity = self.velocity;
+        let mut vertical = UVec::along(up, velocity.project(up));
+        if self.grounded && velocity.project(up) < Unit::ZERO {
+            velocity -= vertical;
+            vertical = UVec::ZERO;
+        }
+
+        let (gravity_step, velocity_carry) = (pull + self.velocity_carry).div_rem(tick_rate);
+        self.velocity_carry = velocity_carry;
+        velocity += gravity_step;
+        vertical += gravity_step;
+
+        if let Some(damping) = damping {
+            let planar = velocity - vertical;
+            velocity = vertical + planar * tick_rate / (tick_rate + damping);
+        }
+
+        self.velocity = velocity;
+        (velocity + carried + self.position_carry).div_rem(tick_rate)
+    }
+
+    fn finish_step(&mut self, step: UVec, carry: UVec, moved: UVec, grounded: bool) {
+        self.position_carry = if moved == step { carry } else { UVec::ZERO };
+        self.position += moved;
+        self.grounded = grounded;
+    }
+}
+
+#[derive(Component)]
+pub struct CharacterController {
+    pub options: MoveShapeOptions,
+    pub mass: f32,
+}
+
+impl Default for CharacterController {
+    fn default() -> Self {
+        CharacterController {
+            options: MoveShapeOptions {
+                // wider than the half-unit diagonal, so rounding the result onto the unit grid
+                // never sinks the collider into what it stopped against
+                offset: CharacterLength::Absolute(0.05),
+                autostep: Some(CharacterAutostep {
+                    max_height: CharacterLength::Absolute(0.3),
+                    min_width: CharacterLength::Absolute(0.2),
+                    include_dynamic_bodies: false,
+                }),
+                snap_to_ground: Some(CharacterLength::Absolute(0.3)),
+                max_slope_climb_angle: 50.0_f32.to_radians(),
+                min_slope_slide_angle: 45.0_f32.to_radians(),
+                apply_impulse_to_dynamic_bodies: true,
+                ..default()
+            },
+            mass: 2.0,
+        }
+    }
+}
+
+#[derive(SystemSet, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum MotionSet {
+    Move,
+    React,
+    Drive,
+}
+
+#[derive(Event)]
+pub struct CharacterCollisionEvent {
+    pub entity: Entity,
+    pub other: Entity,
+    pub normal: Vec3,
+}
+
+#[derive(Component)]
+pub struct SlideSurface;
+
+#[derive(Default, Component, Clone, Copy, PartialEq)]
+pub enum SurfaceType {
+    #[default]
+    Normal,
+    Ice,
+    Sand,
+    Mud,
+    Conveyor(Vec3),
+    Bouncy,
+}
+
+impl SurfaceType {
+    pub fn traction(&self) -> f32 {
+        match self {
+            SurfaceType::Ice => 0.3,
+            SurfaceType::Sand => 0.6,
+            SurfaceType::Mud => 0.5,
+            _ => 1.0,
+        }
+    }
+
+    pub fn max_speed(&self) -> f32 {
+        match self {
+            SurfaceType::Sand => 0.6,
+            SurfaceType::Mud => 0.35,
+            _ => 1.0,
+        }
+    }
+
+    pub fn release_friction(&self) -> Option<f32> {
+        match self {
+            SurfaceType::Ice => Some(4.0),
+            _ => None,
+        }
+    }
+
+    pub fn belt_velocity(&self) -> Vec3 {
+        match self {
+            SurfaceType::Conveyor(velocity) => *velocity,
+            _ => Vec3::ZERO,
+        }
+    }
+
+    pub fn restitution(&self) -> f32 {
+        match self {
+            SurfaceType::Bouncy => 0.8,
+            _ => 0.0,
+        }
+    }
+}
+
+#[derive(Default, Component)]
+pub struct Footing(pub SurfaceType);
+
+#[derive(Component)]
+pub struct Coasting {
+    friction: f32,
+}
+
+impl Coasting {
+    pub fn new(friction: f32) -> Self {
+        Coasting { friction }
+    }
+}
+
 #[derive(Bundle)]
 pub struct MovementBundle {
-    pub rigidbody: RigidBody,
+    pub controller: CharacterController,
     pub collider: Collider,
-    pub external_impulse: ExternalImpulse,
-    pub velocity: Velocity,
-    pub friction: Friction,
-    pub damping: Damping,
-    pub gravity_scale: GravityScale,
+    pub damping: LinearDamping,
     pub direction: Direction,
+    pub drift: Drift,
+    pub carried: Carried,
+    pub push: Push,
+    pub footing: Footing,
+    pub gravity: Gravity,
+    pub ground_probe: GroundProbe,
+    pub ground_contact: GroundContact,
     pub character: Character,
     pub momentum: Momentum,
-    pub locked_axes: LockedAxes,
     pub speed: Speed,
+    pub fixed_motion: FixedMotion,
 }
 
 impl Default for MovementBundle {
     fn default() -> Self {
         MovementBundle {
-            rigidbody: RigidBody::KinematicPositionBased,
+            controller: CharacterController::default(),
             collider: Collider::default(),
-            external_impulse: ExternalImpulse::default(),
-            velocity: Velocity::default(),
-            friction: Friction::default(),
-            damping: Damping {
-                linear_damping: 2.0,
-                ..default()
-            },
-            gravity_scale: GravityScale::default(),
+            damping: LinearDamping(2.0),
             direction: Direction::default(),
+            drift: Drift::default(),
+            carried: Carried::default(),
+            push: Push::default(),
+            footing: Footing::default(),
+            gravity: Gravity::default(),
+            ground_probe: GroundProbe::default(),
+            ground_contact: GroundContact::default(),
             character: Character,
             momentum: Momentum::default(),
-            locked_axes: LockedAxes::ROTATION_LOCKED,
             speed: Speed::default(),
+            fixed_motion: FixedMotion::default(),
         }
     }
 }
 
 impl MovementBundle {
-    pub fn with_rigidbody(mut self, rigidbody: RigidBody) -> Self {
-        self.rigidbody = rigidbody;
-        self
-    }
-
     pub fn with_collider(mut self, collider: Collider) -> Self {
         self.collider = collider;
         self
     }
 
-    pub fn with_impulse(mut self, external_impulse: ExternalImpulse) -> Self {
-        self.external_impulse = external_impulse;
-        self
-    }
-
-    pub fn with_velocity(mut self, velocity: Velocity) -> Self {
-        self.velocity = velocity;
-        self
-    }
-
-    pub fn with_damping(mut self, damping: Damping) -> Self {
-        self.damping = damping;
-        self
-    }
-
-    pub fn with_friction(mut self, friction: Friction) -> Self {
-        self.friction = friction;
-        self
-    }
-
     pub fn with_gravity_scale(mut self, gravity_scale: f32) -> Self {
-        self.gravity_scale = GravityScale(gravity_scale);
+        self.gravity.scale = gravity_scale;
         self
     }
 }
 
+type RotateQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static mut Transform,
+        &'static Direction,
+        &'static Speed,
+        &'static Gravity,
+    ),
+    (With<Character>, With<Grounded>, Without<Coasting>),
+>;
+
 fn rotate_to_direction(
-    time: Res<Time>,
-    mut query: Query<(&mut Transform, &Direction, &Speed), (With<Character>, With<Grounded>)>,
+    fixed_time: Res<FixedTime>,
+    mut query: RotateQuery,
     mut rotation_target: Local<Transform>,
 ) {
-    for (mut transform, direction, speed) in &mut query {
+    for (mut transform, direction, speed, gravity) in &mut query {
         rotation_target.translation = transform.translation;
-        let flat_velo_direction = Vec3::new(direction.0.x, 0.0, direction.0.z).normalize_or_zero();
+        let flat_velo_direction = gravity.flatten(direction.get());
         if flat_velo_direction != Vec3::ZERO {
             let target_position = rotation_target.translation + flat_velo_direction;
 
-            rotation_target.look_at(target_position, Vec3::Y);
-            let turn_speed = speed.current * 0.85;
+            rotation_target.look_at(target_position, gravity.up);
+            let turn_speed = speed.turn_speed();
 
-            transform.rotation = transform
-                .rotation
-                .slerp(rotation_target.rotation, time.delta_seconds() * turn_speed);
+            transform.rotation = transform.rotation.slerp(
+                rotation_target.rotation,
+                fixed_time.period.as_secs_f32() * turn_speed,
+            );
         }
     }
 }
 
-fn handle_speed(
-    time: Res<Time>,
-    mut query: Query<(&mut Momentum, &mut Speed, &Direction), With<Grounded>>,
-) {
-    for (mut momentum, mut speed, direction) in &mut query {
+type SpeedQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static mut Momentum,
+        &'static mut Speed,
+        &'static Direction,
+        &'static Footing,
+    ),
+    (With<Grounded>, Without<Coasting>),
+>;
+
+fn handle_speed(fixed_time: Res<FixedTime>, mut query: SpeedQuery) {
+    let seconds = fixed_time.period.as_secs_f32();
+    for (mut momentum, mut speed, direction, footing) in &mut query {
         if direction.is_active() {
-            speed.accelerate(time.delta(), time.delta_seconds());
-            momentum.set(speed.current);
+            speed.accelerate(fixed_time.period, seconds * footing.0.traction());
+            speed.limit(footing.0.max_speed());
+            if !direction.is_full_tilt() {
+                speed.limit_to_walk();
+            }
+            momentum.set(speed.current());
             speed.reset_reset_timer();
         } else {
-            momentum.reset();
-            speed.tick_reset_timer(time.delta());
+            match footing.0.release_friction() {
+                Some(friction) => {
+                    let slid_momentum = momentum.get() - Unit::from(friction * seconds);
+                    momentum.set(slid_momentum.max(Unit::ZERO));
+                }
+                None => momentum.reset(),
+            }
+            speed.tick_reset_timer(fixed_time.period);
             if speed.should_reset() {
                 speed.reset();
             }
@@ -235,32 +641,445 @@ fn handle_speed(
     }
 }
 
-pub fn apply_momentum(mut query: Query<(&mut Velocity, &Transform, &Momentum)>) {
-    for (mut velocity, transform, momentum) in &mut query {
-        let mut speed_to_apply = Vec3::ZERO;
+fn settle_drift(mut query: Query<&mut Drift, Added<Grounded>>) {
+    for mut drift in &mut query {
+        drift.0 = Vec3::ZERO;
+    }
+}
+
+fn handle_coasting(fixed_time: Res<FixedTime>, mut query: Query<(&mut Momentum, &Coasting)>) {
+    for (mut momentum, coasting) in &mut query {
+        let friction = Unit::from(coasting.friction * fixed_time.period.as_secs_f32());
+        let coasted_momentum = momentum.get() - friction;
+        momentum.set(coasted_momentum.max(Unit::ZERO));
+    }
+}
+
+fn apply_conveyors(mut query: Query<(&mut Push, &Footing, Has<Grounded>)>) {
+    for (mut push, footing, is_grounded) in &mut query {
+        push.conveyor = if is_grounded {
+            footing.0.belt_velocity()
+        } else {
+            Vec3::ZERO
+        };
+    }
+}
+
+fn apply_wind_volumes(
+    fixed_time: Res<FixedTime>,
+    rapier_context: Res<RapierContext>,
+    mut gust_phase: Local<f32>,
+    volume_query: Query<(&WindVolume, &GlobalTransform)>,
+    mut query: Query<(&mut Push, &Transform, &Gravity), With<Character>>,
+) {
+    let seconds = fixed_time.period.as_secs_f32();
+    *gust_phase += seconds;
+    for (mut push, transform, gravity) in &mut query {
+        let is_volume = |entity| volume_query.contains(entity);
+        let mut volume = None;
+        rapier_context.intersections_with_point(
+            transform.translation,
+            QueryFilter::default().predicate(&is_volume),
+            |entity| {
+                volume = volume_query.get(entity).ok();
+                false
+            },
+        );
+
+        push.wind = match volume {
+            Some((WindVolume::Constant(force), _)) => *force,
+            Some((WindVolume::Turbulent { force, gust }, _)) => {
+                let strength = 1.0 + gust * (*gust_phase * 2.3).sin();
+                let sway = Vec3::new((*gust_phase * 1.7).sin(), 0.0, (*gust_phase * 2.9).cos())
+                    * force.length()
+                    * *gust;
+                *force * strength + sway
+            }
+            Some((WindVolume::Updraft(strength), volume_transform)) => {
+                volume_transform.up() * *strength
+            }
+            None => Vec3::ZERO,
+        };
+
+        let sideways_wind = push.wind - gravity.up * push.wind.dot(gravity.up);
+        let blown = push.blown + (sideways_wind - push.blown * WIND_DRAG) * seconds;
+        push.blown = if volume.is_none() && blown.length() < WIND_SETTLE_SPEED {
+            Vec3::ZERO
+        } else {
+            blown
+        };
+    }
+}
+
+pub fn apply_momentum(
+    fixed_time: Res<FixedTime>,
+    mut query: Query<(
+        &mut FixedMotion,
+        &Transform,
+        &Momentum,
+        &Drift,
+        &Push,
+        &Gravity,
+    )>,
+) {
+    for (mut motion, transform, momentum, drift, push, gravity) in &mut query {
+        let mut speed_to_apply = UVec::ZERO;
         let mut should_change_velocity: bool = false;
 
         if momentum.is_any() {
             should_change_velocity = true;
-            let forward = transform.forward();
-            speed_to_apply += forward * momentum.get();
+            let flat_forward = gravity.flatten(transform.forward());
+            speed_to_apply += UVec::along(flat_forward, momentum.get());
         }
 
+        if drift.0 != Vec3::ZERO {
+            should_change_velocity = true;
+            speed_to_apply += drift.0.into();
+        }
+
+        let lift = Unit::from(push.wind.dot(gravity.up) * fixed_time.period.as_secs_f32());
+        let rise = gravity.rise(&motion) + lift;
         if should_change_velocity {
-            velocity.linvel.x = speed_to_apply.x;
-            velocity.linvel.z = speed_to_apply.z;
+            motion.set_velocity(speed_to_apply + UVec::along(gravity.up, rise));
+        } else if lift != Unit::ZERO {
+            gravity.set_rise(&mut motion, rise);
         }
     }
 }
 
+fn place_characters(mut query: Query<(&mut FixedMotion, &Transform), Added<FixedMotion>>) {
+    for (mut motion, transform) in &mut query {
+        motion.position = transform.translation.into();
+    }
+}
+
+type StepQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static mut FixedMotion,
+        &'static mut Transform,
+        &'static CharacterController,
+        &'static Collider,
+        &'static Momentum,
+        &'static Carried,
+        &'static Push,
+        &'static Gravity,
+        &'static LinearDamping,
+    ),
+    With<Character>,
+>;
+
+fn step_fixed_motion(
+    fixed_time: Res<FixedTime>,
+    rapier_config: Res<RapierConfiguration>,
+    mut rapier_context: ResMut<RapierContext>,
+    mut collision_events: EventWriter<CharacterCollisionEvent>,
+    rigidbody_query: Query<&RigidBody>,
+    mut query: StepQuery,
+) {
+    let tick_rate = (1.0 / fixed_time.period.as_secs_f32()).round() as i32;
+    for (
+        entity,
+        mut motion,
+        mut transform,
+        controller,
+        collider,
+        momentum,
+        carried,
+        push,
+        gravity,
+        damping,
+    ) in &mut query
+    {
+        let pull = -gravity.up * rapier_config.gravity.length() * gravity.strength * gravity.scale;
+        let damping = (!momentum.is_any()).then_some(damping.0.round().max(0.0) as i32);
+        // conveyors and wind carry the character along like a platform does, on top of its own
+        // velocity, so they never wipe out a knockback or a jump
+        let environment = push.conveyor - gravity.up * push.conveyor.dot(gravity.up) + push.blown;
+        let (step, position_carry) = motion.plan_step(
+            gravity.up,
+            pull.into(),
+            damping,
+            (carried.0 + environment).into(),
+            tick_rate,
+        );
+
+        let mut collisions = Vec::new();
+        let output = rapier_context.move_shape(
+            step.into(),
+            collider,
+            motion.position.into(),
+            transform.rotation,
+            controller.mass,
+            &controller.options,
+            QueryFilter::default()
+                .exclude_sensors()
+                .exclude_collider(entity),
+            |collision| collisions.push(collision),
+        );
+
+        let moved = UVec::from(output.effective_translation);
+        motion.finish_step(step, position_carry, moved, output.grounded);
+        transform.translation = motion.position.into();
+
+        for collision in collisions {
+            let normal = collision.toi.normal1;
+            collision_events.send(CharacterCollisionEvent {
+                entity,
+                other: collision.entity,
+                normal,
+            });
+
+            if normal.dot(gravity.up) > GROUND_NORMAL_Y {
+                // rounding onto the unit grid can leave the feet just past the controller's own
+                // ground prediction, so landing on the floor counts as well
+                motion.grounded = true;
+                continue;
+            }
+
+            let is_pushable = matches!(
+                rigidbody_query.get(collision.entity),
+                Ok(RigidBody::Dynamic)
+            );
+            if is_pushable {
+                continue;
+            }
+
+            let into_surface = motion.velocity.project(normal);
+            if into_surface < Unit::ZERO {
+                motion.velocity -= UVec::along(normal, into_surface);
+            }
+        }
+    }
+}
+
+fn apply_gravity_volumes(
+    fixed_time: Res<FixedTime>,
+    rapier_context: Res<RapierContext>,
+    volume_query: Query<(&GravityVolume, &GlobalTransform)>,
+    mut query: Query<
+        (
+            &mut Gravity,
+            &mut Transform,
+            &mut CharacterController,
+            Has<HoldTilt>,
+        ),
+        With<Character>,
+    >,
+) {
+    for (mut gravity, mut transform, mut controller, holds_tilt) in &mut query {
+        let is_volume = |entity| volume_query.contains(entity);
+        let mut volume = None;
+        rapier_context.intersections_with_point(
+            transform.translation,
+            QueryFilter::default().predicate(&is_volume),
+            |entity| {
+                volume = volume_query.get(entity).ok();
+                false
+            },
+        );
+
+        let (up, strength) = match volume {
+            Some((GravityVolume::Low(factor), _)) => (Vec3::Y, *factor),
+            Some((GravityVolume::Reversed, _)) => (Vec3::NEG_Y, 1.0),
+            Some((GravityVolume::Spherical, volume_transform)) => {
+                let away_from_centre = transform.translation - volume_transform.translation();
+                (away_from_centre.try_normalize().unwrap_or(Vec3::Y), 1.0)
+            }
+            None => (Vec3::Y, 1.0),
+        };
+
+        gravity.up = up;
+        gravity.strength = strength;
+        controller.options.up = up;
+
+        if !holds_tilt && transform.up().dot(up) < 0.9999 {
+            let tilt = Quat::from_rotation_arc(transform.up(), up);
+            let blend = (fixed_time.period.as_secs_f32() * UPRIGHT_SPEED).min(1.0);
+            transform.rotation = Quat::IDENTITY.slerp(tilt, blend) * transform.rotation;
+        }
+    }
+}
+
+fn apply_ground_snap(
+    mut query: Query<(&GroundProbe, &mut CharacterController), Changed<GroundProbe>>,
+) {
+    for (probe, mut controller) in &mut query {
+        controller.options.snap_to_ground = Some(CharacterLength::Absolute(probe.snap_distance));
+    }
+}
+
+fn apply_movement_profiles(
+    profiles: Res<Assets<MovementProfile>>,
+    mut profile_events: EventReader<AssetEvent<MovementProfile>>,
+    mut query: Query<(
+        Ref<Handle<MovementProfile>>,
+        &mut Speed,
+        &mut Direction,
+        &mut LinearDamping,
+    )>,
+) {
+    let mut changed_profiles = HashSet::new();
+    for event in profile_events.iter() {
+        if let AssetEvent::Created { handle } | AssetEvent::Modified { handle } = event {
+            changed_profiles.insert(handle.clone_weak());
+        }
+    }
+
+    for (handle, mut speed, mut direction, mut damping) in &mut query {
+        if !handle.is_changed() && !changed_profiles.contains(&*handle) {
+            continue;
+        }
+
+        let Some(profile) = profiles.get(&handle) else {
+            continue;
+        };
+
+        if let Some(field) = profile.invalid_field() {
+            warn!("skipping movement profile with an invalid {field}");
+            continue;
+        }
+
+        speed.retune(profile);
+        direction.deadzone = profile.deadzone.min(1.0);
+        direction.run_threshold = profile.run_threshold.min(1.0);
+        damping.0 = profile.damping;
+    }
+}
+
 pub struct PhysicsPlugin;
 
 impl Plugin for PhysicsPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
+        // a fixed order between every system in the tick, rather than whichever finishes first,
+        // so the same inputs step the same way on every run
+        app.edit_schedule(FixedUpdate, |schedule| {
+            schedule.set_executor_kind(ExecutorKind::SingleThreaded);
+        })
+        .configure_sets(
+            FixedUpdate,
+            (MotionSet::Move, MotionSet::React, MotionSet::Drive).chain(),
+        )
+        .add_event::<CharacterCollisionEvent>()
+        .add_systems(
+            FixedUpdate,
+            (
+                (place_characters, apply_ground_snap, step_fixed_motion)
+                    .chain()
+                    .in_set(MotionSet::Move)
+                    .run_if(in_state(GameState::Gameplay)),
+                apply_deferred
+                    .after(MotionSet::React)
+                    .before(MotionSet::Drive),
+                (
+                    apply_gravity_volumes,
+                    rotate_to_direction,
+                    handle_speed,
+                    handle_coasting,
+                    settle_drift,
+                    apply_conveyors,
+                    apply_wind_volumes,
+                    apply_momentum,
+                )
+                    .chain()
+                    .in_set(MotionSet::Drive)
+                    .run_if(in_state(GameState::Gameplay)),
+            ),
+        )
+        .add_systems(
             Update,
-            (rotate_to_direction, apply_momentum, handle_speed)
-                .run_if(in_state(GameState::Gameplay)),
+            apply_movement_profiles.run_if(in_state(GameState::Gameplay)),
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fall(ticks: usize) -> FixedMotion {
+        let mut motion = FixedMotion::default();
+        motion.set_velocity(Vec3::new(5.0, 12.0, -3.0).into());
+        let pull = UVec::from(Vec3::NEG_Y * 9.81 * 3.0);
+        for _ in 0..ticks {
+            let (step, carry) = motion.plan_step(Vec3::Y, pull, Some(2), Vec3::X.into(), 60);
+            motion.finish_step(step, carry, step, false);
+        }
+        motion
+    }
+
+    #[test]
+    fn movement_profiles_reject_values_that_would_panic() {
+        let profile = || MovementProfile {
+            base_speed: 3.0,
+            max_speed: 24.0,
+            acceleration: 2.5,
+            acceleration_delay: 0.6,
+            reset_delay: 0.25,
+            damping: 2.0,
+            turn_factor: 0.85,
+            deadzone: 0.3,
+            walk_speed: 6.0,
+            run_threshold: 0.7,
+        };
+        assert_eq!(profile().invalid_field(), None);
+        assert_eq!(
+            MovementProfile {
+                acceleration_delay: -1.0,
+                ..profile()
+            }
+            .invalid_field(),
+            Some("acceleration_delay")
+        );
+        assert_eq!(
+            MovementProfile {
+                reset_delay: f32::NAN,
+                ..profile()
+            }
+            .invalid_field(),
+            Some("reset_delay")
+        );
+        assert_eq!(
+            MovementProfile {
+                reset_delay: 1e30,
+                ..profile()
+            }
+            .invalid_field(),
+            Some("reset_delay")
+        );
+        assert_eq!(
+            MovementProfile {
+                damping: -60.0,
+                ..profile()
+            }
+            .invalid_field(),
+            Some("damping")
+        );
+    }
+
+    #[test]
+    fn same_inputs_step_to_the_same_units() {
+        let first = fall(240);
+        let second = fall(240);
+        assert_ne!(first.position, UVec::ZERO);
+        assert_eq!(first.position, second.position);
+        assert_eq!(first.velocity, second.velocity);
+        assert_eq!(first.velocity_carry, second.velocity_carry);
+        assert_eq!(first.position_carry, second.position_carry);
+    }
+
+    #[test]
+    fn steady_velocity_travels_exactly_its_distance() {
+        let mut motion = FixedMotion::default();
+        let velocity = UVec::int(100, 0, -7);
+        motion.set_velocity(velocity);
+        for _ in 0..120 {
+            let (step, carry) = motion.plan_step(Vec3::Y, UVec::ZERO, None, UVec::int(3, 0, 0), 60);
+            motion.finish_step(step, carry, step, false);
+        }
+        assert_eq!(motion.position, (velocity + UVec::int(3, 0, 0)) * 2);
+        assert_eq!(motion.velocity, velocity);
+    }
+}
@@ -0,0 +1,149 @@
+use bevy::ecs::query::Has;
+use bevy::prelude::*;
+
+use crate::{
+    core::{Character, GameState, UVec},
+    physics::{Carried, FixedMotion, Gravity, GroundContact, Grounded, MotionSet},
+};
+
+#[derive(Component)]
+pub struct PlatformPath {
+    waypoints: Vec<Vec3>,
+    speed: f32,
+    next: usize,
+    returning: bool,
+}
+
+impl PlatformPath {
+    pub fn new(waypoints: Vec<Vec3>, speed: f32) -> Self {
+        PlatformPath {
+            waypoints,
+            speed,
+            next: 0,
+            returning: false,
+        }
+    }
+
+    fn advance(&mut self) {
+        let last = self.waypoints.len() - 1;
+        if last == 0 {
+            return;
+        }
+
+        if self.next == last {
+            self.returning = true;
+        } else if self.next == 0 {
+            self.returning = false;
+        }
+
+        self.next = if self.returning {
+            self.next - 1
+        } else {
+            self.next + 1
+        };
+    }
+}
+
+/// Radians per second around each axis
+#[derive(Component)]
+pub struct PlatformSpin(pub Vec3);
+
+#[derive(Default, Component)]
+pub struct PlatformVelocity {
+    linear: Vec3,
+    angular: Vec3,
+}
+
+fn follow_platform_paths(
+    fixed_time: Res<FixedTime>,
+    mut query: Query<(&mut PlatformPath, &mut Transform, &mut PlatformVelocity)>,
+) {
+    let seconds = fixed_time.period.as_secs_f32();
+    for (mut path, mut transform, mut velocity) in &mut query {
+        if path.waypoints.is_empty() {
+            continue;
+        }
+
+        let mut to_target = path.waypoints[path.next] - transform.translation;
+        if to_target.length() <= path.speed * seconds {
+            path.advance();
+            to_target = path.waypoints[path.next] - transform.translation;
+        }
+
+        velocity.linear = to_target.normalize_or_zero() * path.speed;
+        transform.translation += velocity.linear * seconds;
+    }
+}
+
+fn spin_platforms(
+    fixed_time: Res<FixedTime>,
+    mut query: Query<(&PlatformSpin, &mut Transform, &mut PlatformVelocity)>,
+) {
+    let seconds = fixed_time.period.as_secs_f32();
+    for (spin, mut transform, mut velocity) in &mut query {
+        velocity.angular = spin.0;
+        transform.rotate(Quat::from_scaled_axis(spin.0 * seconds));
+    }
+}
+
+type RiderQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static mut Transform,
+        &'static mut FixedMotion,
+        &'static mut Carried,
+        &'static GroundContact,
+        &'static Gravity,
+        Has<Grounded>,
+    ),
+    With<Character>,
+>;
+
+fn carry_riders(
+    fixed_time: Res<FixedTime>,
+    platform_query: Query<(&Transform, &PlatformVelocity), Without<Character>>,
+    mut rider_query: RiderQuery,
+) {
+    for (mut transform, mut motion, mut carried, contact, gravity, is_grounded) in &mut rider_query
+    {
+        let platform = contact
+            .entity
+            .filter(|_| is_grounded)
+            .and_then(|floor| platform_query.get(floor).ok());
+
+        match platform {
+            Some((platform_transform, platform_velocity)) => {
+                let lever = transform.translation - platform_transform.translation;
+                carried.0 = platform_velocity.linear + platform_velocity.angular.cross(lever);
+                let turn =
+                    platform_velocity.angular.dot(gravity.up) * fixed_time.period.as_secs_f32();
+                transform.rotate_axis(gravity.up, turn);
+            }
+            None if is_grounded => carried.0 = Vec3::ZERO,
+            None => {
+                let rise = carried.0.dot(gravity.up);
+                if rise != 0.0 {
+                    let velocity = motion.velocity() + UVec::along(gravity.up, rise.into());
+                    motion.set_velocity(velocity);
+                    carried.0 -= gravity.up * rise;
+                }
+            }
+        }
+    }
+}
+
+pub struct PlatformPlugin;
+
+impl Plugin for PlatformPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            (
+                (follow_platform_paths, spin_platforms).before(MotionSet::Move),
+                carry_riders.in_set(MotionSet::React),
+            )
+                .run_if(in_state(GameState::Gameplay)),
+        );
+    }
+}
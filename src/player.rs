@@ -1,37 +1,129 @@
 use bevy::ecs::query::Has;
 use bevy::prelude::*;
-use bevy_hanabi::prelude::*;
 use bevy_rapier3d::prelude::*;
-use leafwing_input_manager::prelude::*;
 use std::time::Duration;
 
 use crate::{
     animation::{Animated, AnimationCharacterMap, AnimationInit, AnimationTransitionEvent},
-    assets::{CharacterCache, PlayerAnimationCache},
+    assets::{CharacterCache, MovementProfileCache, PlayerAnimationCache},
     camera::MainCamera,
-    core::{GameState, IndexPointer},
-    input::{InputListenerBundle, PlayerAction},
+    core::{GameState, IndexPointer, UVec, Unit},
+    health::{detect_hazards, take_damage, DamageEvent, Health, RespawnEvent, SpawnPoint},
+    input::{InputListenerBundle, PlayerAction, TickActions},
+    ledge::{handle_ledges, Ledge},
     particles::OneTimeParticleBundle,
-    physics::{Direction, Grounded, Momentum, MovementBundle, Speed},
+    physics::{
+        CharacterCollisionEvent, Coasting, Direction, Drift, FixedMotion, Footing, Gravity,
+        GroundContact, GroundProbe, Grounded, HoldTilt, Momentum, MotionSet, MovementBundle,
+        SlideSurface, Speed, SurfaceType,
+    },
+    water::Breath,
 };
 
+pub const PLAYER_GRAVITY_SCALE: f32 = 3.0;
+const PLAYER_BREATH: f32 = 8.0;
+const JUMP_CHAIN_SPEED: f32 = 8.0;
+const HARD_LANDING_SPEED: f32 = 12.0;
+pub const LONG_JUMP_SPEED_FRACTION: f32 = 0.5;
+const LONG_JUMP_VELOCITY: f32 = 7.0;
+const LONG_JUMP_BOOST: f32 = 1.4;
+const DIVE_SPEED: f32 = 16.0;
+const DIVE_VELOCITY: f32 = 4.0;
+const DIVE_TILT: f32 = std::f32::consts::FRAC_PI_3;
+const DIVE_RECOVER_VELOCITY: f32 = 6.0;
+const BELLY_SLIDE_FRICTION: f32 = 12.0;
+const BELLY_SLIDE_STOP_SPEED: f32 = 1.0;
+const WALL_KICK_SPEED: f32 = 6.0;
+const WALL_KICK_VELOCITY: f32 = 12.0;
+const WALL_CHECK_DISTANCE: f32 = 0.2;
+const AIR_CONTROL_ACCEL: f32 = 20.0;
+const AIR_CONTROL_BRAKE: f32 = 10.0;
+const AIR_CONTROL_MAX_DRIFT: f32 = 6.0;
+const SKID_SPEED_FRACTION: f32 = 0.5;
+const SKID_ANGLE: f32 = 2.0;
+const SKID_FRICTION: f32 = 40.0;
+const SKID_STOP_SPEED: f32 = 2.0;
+const SIDE_FLIP_VELOCITY: f32 = 15.0;
+const SIDE_FLIP_SPEED: f32 = 4.0;
+const GROUND_POUND_HANG_TIME: f32 = 0.3;
+const GROUND_POUND_GRAVITY_SCALE: f32 = 8.0;
+const GROUND_POUND_SPEED: f32 = 20.0;
+const MAX_WALKABLE_SLOPE: f32 = std::f32::consts::FRAC_PI_4;
+const MIN_SLOPE: f32 = 0.05;
+const SLOPE_SPEED_GAIN: f32 = 20.0;
+const SLIDE_ACCEL: f32 = 25.0;
+const SLIDE_FRICTION: f32 = 4.0;
+const BUTT_SLIDE_FRICTION: f32 = 2.0;
+const BUTT_SLIDE_MIN_SPEED: f32 = 6.0;
+const BUTT_SLIDE_STEER: f32 = 0.6;
+const PLAYER_HEALTH: u32 = 3;
+pub const GROUND_SKIN: f32 = 0.1;
+const WALK_MODIFIER_TILT: f32 = 0.5;
+const KNOCKBACK_SPEED: f32 = 6.0;
+const KNOCKBACK_LIFT: f32 = 8.0;
+
 #[derive(Resource, Default)]
 pub struct PlayerData {
     pub player_position: Vec3,
     pub held_object_position: Vec3,
     pub held_object_index: IndexPointer,
-    pub distance_from_floor: f32,
-    pub floor_normal: Vec3,
-    pub speed: f32,
-    pub defacto_speed: f32,
+    pub player_forward: Vec3,
+    pub underwater: bool,
+    pub camera_target: Vec3,
+}
+
+#[derive(Component, Default)]
+pub struct PlayerMoves {
     pub kicked_wall: Option<Entity>,
     pub jump_stage: u8,
+    pub jump_window: Timer,
+    pub coyote_timer: Option<Timer>,
+    pub launch_lockout: Option<Timer>,
+    pub wall_contact: Option<(Entity, Vec3)>,
+    pub wall_kick_window: Timer,
+    pub ground_pound_timer: Timer,
+    pub ledge: Option<Ledge>,
+    pub ledge_cooldown: Timer,
+}
+
+impl PlayerMoves {
+    pub fn jump_velocity(&self) -> Unit {
+        match self.jump_stage {
+            2 => Unit::from(13.0),
+            3 => Unit::from(17.0),
+            _ => Unit::from(10.0),
+        }
+    }
+
+    pub fn advance_jump_stage(&mut self, momentum: Unit) {
+        let can_chain = self.jump_stage > 0
+            && self.jump_stage < 3
+            && !self.jump_window.finished()
+            && momentum >= Unit::from(JUMP_CHAIN_SPEED);
+
+        self.jump_stage = if can_chain { self.jump_stage + 1 } else { 1 };
+    }
+
+    pub fn open_jump_window(&mut self) {
+        self.jump_window = Timer::from_seconds(0.25, TimerMode::Once);
+    }
+
+    pub fn touch_wall(&mut self, wall: Entity, normal: Vec3) {
+        self.wall_contact = Some((wall, normal));
+        self.wall_kick_window = Timer::from_seconds(0.2, TimerMode::Once);
+    }
+
+    pub fn start_coyote_time(&mut self) {
+        self.coyote_timer = Some(Timer::from_seconds(0.1, TimerMode::Once));
+    }
 }
 
 #[derive(Event)]
-pub struct PlayerStateTransitionEvent {
-    pub current_state: PlayerState,
-    pub new_state: PlayerState,
+pub struct LandingEvent {
+    pub entity: Entity,
+    pub surface: Entity,
+    pub position: Vec3,
+    pub impact_speed: f32,
 }
 
 #[derive(Component, Default, Clone, Copy, Deref)]
@@ -40,6 +132,30 @@ pub struct Player {
     pub state: PlayerState,
 }
 
+impl Player {
+    pub fn is_free_moving(&self) -> bool {
+        matches!(
+            self.state,
+            PlayerState::Idle
+                | PlayerState::Walking
+                | PlayerState::Running
+                | PlayerState::Rising
+                | PlayerState::Freefall
+                | PlayerState::Carrying
+        )
+    }
+}
+
+impl PlayerState {
+    pub fn air_control(&self) -> f32 {
+        match self {
+            PlayerState::Rising | PlayerState::Freefall | PlayerState::Walljumping => 1.0,
+            PlayerState::LongJumping => 0.2,
+            _ => 0.0,
+        }
+    }
+}
+
 #[derive(Default, Clone, Copy, PartialEq, Eq)]
 pub enum PlayerState {
     Diving,
@@ -48,16 +164,27 @@ pub enum PlayerState {
     Idle,
     Walking,
     Running,
+    Skidding,
     LongJumping,
     Rising,
     Freefall,
     Walljumping,
+    GroundPounding,
+    Hanging,
+    Knockback,
+    Crouching,
+    Backflipping,
+    Swimming,
     Carrying,
     ButtSliding,
     Sliding,
 }
 
-fn spawn_player(mut commands: Commands, characters: Res<CharacterCache>) {
+fn spawn_player(
+    mut commands: Commands,
+    characters: Res<CharacterCache>,
+    movement_profiles: Res<MovementProfileCache>,
+) {
     commands.spawn((
         SceneBundle {
             scene: characters.uli.clone_weak(),
@@ -67,65 +194,193 @@ fn spawn_player(mut commands: Commands, characters: Res<CharacterCache>) {
             state: PlayerState::Idle,
         },
         Animated,
-        MovementBundle {
-            collider: Collider::capsule_y(0.5, 0.5),
-            ..default()
-        },
+        MovementBundle::default()
+            .with_collider(standing_collider())
+            .with_gravity_scale(PLAYER_GRAVITY_SCALE),
+        movement_profiles.player.clone_weak(),
+        Health::new(PLAYER_HEALTH),
+        SpawnPoint(Vec3::ZERO),
+        Breath::new(PLAYER_BREATH),
+        PlayerMoves::default(),
         InputListenerBundle::input_map(),
     ));
 }
 
 fn update_player_data(
     mut player_data: ResMut<PlayerData>,
-    player_query: Query<&Transform, With<Player>>,
+    player_query: Query<(&Transform, &PlayerMoves), With<Player>>,
 ) {
-    for transform in &player_query {
+    for (transform, moves) in &player_query {
         player_data.player_position = transform.translation;
+        player_data.player_forward = transform.forward();
+        player_data.camera_target = match moves.ledge {
+            Some(ledge) => transform.translation.lerp(ledge.point, 0.5),
+            None => transform.translation,
+        };
     }
 }
 
-fn handle_grounded(
+type GroundedQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static Player,
+        &'static Transform,
+        &'static mut FixedMotion,
+        &'static mut Footing,
+        &'static mut GroundContact,
+        &'static mut PlayerMoves,
+        &'static GroundProbe,
+        &'static Gravity,
+        Has<Grounded>,
+    ),
+>;
+
+pub fn handle_grounded(
     mut commands: Commands,
-    mut player_data: ResMut<PlayerData>,
-    player_query: Query<(Entity, &Transform, Has<Grounded>), With<Player>>,
+    mut landing_events: EventWriter<LandingEvent>,
+    mut player_query: GroundedQuery,
+    surface_query: Query<&SurfaceType>,
     rapier_context: Res<RapierContext>,
 ) {
-    for (entity, transform, has_grounded) in &player_query {
-        let ray_pos = transform.translation;
-        let ray_dir = Vec3::Y * -1.0;
-        let max_distance = 1.1;
-        let solid = true;
-        let filter = QueryFilter::exclude_dynamic().exclude_sensors();
-
-        let ray_result =
-            rapier_context.cast_ray_and_get_normal(ray_pos, ray_dir, max_distance, solid, filter);
-
-        if let Some((_, intersection)) = ray_result {
-            player_data.floor_normal = intersection.normal;
-            player_data.distance_from_floor = intersection.toi;
-            player_data.kicked_wall = None;
+    for (
+        entity,
+        player,
+        transform,
+        mut motion,
+        mut footing,
+        mut contact,
+        mut moves,
+        probe,
+        gravity,
+        has_grounded,
+    ) in &mut player_query
+    {
+        let filter = QueryFilter::exclude_dynamic()
+            .exclude_sensors()
+            .exclude_collider(entity);
+
+        let rise = gravity.rise(&motion);
+        let is_rising = rise > Unit::ZERO && !has_grounded;
+        let mut max_toi = probe.reach - probe.radius + GROUND_SKIN;
+        if has_grounded {
+            max_toi += probe.snap_distance;
+        }
+
+        let hit = if is_rising || player.state == PlayerState::Hanging {
+            None
+        } else {
+            rapier_context.cast_shape(
+                transform.translation,
+                Quat::IDENTITY,
+                -gravity.up,
+                &Collider::ball(probe.radius),
+                max_toi,
+                filter,
+            )
+        };
+
+        if let Some((floor, toi)) = hit {
+            let point = transform.translation - gravity.up * toi.toi - toi.normal1 * probe.radius;
+            // the sphere meets ledge lips at an angle, so the flat surface under the contact is
+            // what counts as the ground normal, or straight up if there's only the lip
+            let surface_normal = rapier_context
+                .cast_ray_and_get_normal(point + gravity.up * 0.05, -gravity.up, 0.1, true, filter)
+                .map_or(gravity.up, |(_, intersection)| intersection.normal);
+
+            contact.entity = Some(floor);
+            contact.point = point;
+            contact.normal = surface_normal;
+            moves.kicked_wall = None;
+            footing.0 = surface_query.get(floor).copied().unwrap_or_default();
             if !has_grounded {
                 commands.entity(entity).insert(Grounded);
+                moves.open_jump_window();
+                moves.coyote_timer = None;
+                landing_events.send(LandingEvent {
+                    entity,
+                    surface: floor,
+                    position: point,
+                    impact_speed: (-rise).max(Unit::ZERO).into(),
+                });
+                let bounce = (-rise).max(Unit::ZERO).scale(footing.0.restitution());
+                gravity.set_rise(&mut motion, bounce);
+            }
+        } else if has_grounded {
+            contact.entity = None;
+            commands.entity(entity).remove::<Grounded>();
+            if rise <= Unit::ZERO {
+                moves.start_coyote_time();
+            }
+        }
+    }
+}
+
+fn handle_coyote_time(fixed_time: Res<FixedTime>, mut player_query: Query<&mut PlayerMoves>) {
+    for mut moves in &mut player_query {
+        if let Some(coyote_timer) = moves.coyote_timer.as_mut() {
+            coyote_timer.tick(fixed_time.period);
+            if coyote_timer.finished() {
+                moves.coyote_timer = None;
+            }
+        }
+    }
+}
+
+pub fn handle_airborne_state(
+    mut player_query: Query<(&mut Player, &FixedMotion, &Gravity), Without<Grounded>>,
+) {
+    for (mut player, motion, gravity) in &mut player_query {
+        if player.is_free_moving() {
+            let new_state = if gravity.rise(motion) > Unit::ZERO {
+                PlayerState::Rising
+            } else {
+                PlayerState::Freefall
+            };
+
+            if player.state != new_state {
+                player.state = new_state;
             }
         }
     }
 }
 
+fn handle_landing_events(
+    mut commands: Commands,
+    mut landing_events: EventReader<LandingEvent>,
+    mut speed_query: Query<&mut Speed>,
+    particles: Res<crate::particles::ParticleCache>,
+) {
+    for event in landing_events.iter() {
+        if event.impact_speed >= HARD_LANDING_SPEED {
+            if let Ok(mut speed) = speed_query.get_mut(event.entity) {
+                speed.reset();
+            }
+            commands.spawn(OneTimeParticleBundle::new(
+                event.position,
+                4.0,
+                particles.dust.clone_weak(),
+            ));
+        }
+    }
+}
+
 fn set_player_direction(
     mut player_query: Query<
-        (
-            &mut Direction,
-            Option<&Grounded>,
-            &ActionState<PlayerAction>,
-        ),
+        (&mut Direction, &Gravity, Option<&Grounded>, &TickActions),
         With<Player>,
     >,
     camera_query: Query<&Transform, With<MainCamera>>,
 ) {
     let camera_transform = camera_query.single();
-    for (mut direction, grounded, action) in &mut player_query {
+    for (mut direction, gravity, grounded, action) in &mut player_query {
         if grounded.is_some() {
-            direction.set(get_direction_in_camera_space(camera_transform, action));
+            direction.set(get_direction_in_camera_space(
+                camera_transform,
+                gravity,
+                action,
+            ));
         } else {
             if direction.is_any() {
                 direction.set(Vec3::ZERO);
@@ -134,25 +389,72 @@ fn set_player_direction(
     }
 }
 
-fn get_direction_in_camera_space(
+type AirControlQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static Player,
+        &'static Transform,
+        &'static mut Drift,
+        &'static mut Momentum,
+        &'static Gravity,
+        &'static PlayerMoves,
+        &'static TickActions,
+    ),
+    Without<Grounded>,
+>;
+
+fn handle_air_control(
+    fixed_time: Res<FixedTime>,
+    mut player_query: AirControlQuery,
+    camera_query: Query<&Transform, (With<MainCamera>, Without<Player>)>,
+) {
+    let camera_transform = camera_query.single();
+    for (player, transform, mut drift, mut momentum, gravity, moves, action) in &mut player_query {
+        let control = player.air_control();
+        if control == 0.0 || moves.launch_lockout.is_some() {
+            continue;
+        }
+
+        let input = get_direction_in_camera_space(camera_transform, gravity, action);
+        let launch_direction = gravity.flatten(transform.forward());
+        let along = input.dot(launch_direction);
+        let lateral = input - launch_direction * along;
+
+        drift.0 += lateral * AIR_CONTROL_ACCEL * control * fixed_time.period.as_secs_f32();
+        drift.0 = drift.0.clamp_length_max(AIR_CONTROL_MAX_DRIFT * control);
+
+        if along < 0.0 && momentum.is_any() {
+            let brake = along * AIR_CONTROL_BRAKE * control * fixed_time.period.as_secs_f32();
+            let braked_momentum = momentum.get() + Unit::from(brake);
+            momentum.set(braked_momentum.max(Unit::ZERO));
+        }
+    }
+}
+
+pub fn get_direction_in_camera_space(
     camera_transform: &Transform,
-    action: &ActionState<PlayerAction>,
+    gravity: &Gravity,
+    action: &TickActions,
 ) -> Vec3 {
     let mut x = 0.0;
     let mut z = 0.0;
 
-    let mut forward = camera_transform.forward();
-    forward.y = 0.0;
-    forward = forward.normalize();
-
-    let mut right = camera_transform.right();
-    right.y = 0.0;
-    right = right.normalize();
+    let right = gravity.flatten(camera_transform.right());
+    let mut forward = gravity.flatten(camera_transform.forward());
+    if forward == Vec3::ZERO {
+        forward = right.cross(gravity.up);
+    }
 
     if action.pressed(PlayerAction::Move) {
         let axis_pair = action.clamped_axis_pair(PlayerAction::Move).unwrap();
-        x = axis_pair.x();
-        z = axis_pair.y();
+        let tilt = if action.pressed(PlayerAction::Walk) {
+            axis_pair.xy().clamp_length_max(WALK_MODIFIER_TILT)
+        } else {
+            axis_pair.xy()
+        };
+        x = tilt.x;
+        z = tilt.y;
     }
 
     let right_vec: Vec3 = x * right;
@@ -181,39 +483,49 @@ fn play_idle_animation(
     }
 }
 
-fn handle_state_transition_events(
-    mut state_events: EventWriter<PlayerStateTransitionEvent>,
-    player_query: Query<&Player>,
-    mut previous_state: Local<PlayerState>,
-) {
-    for player in &player_query {
-        if player.state != *previous_state {
-            state_events.send(PlayerStateTransitionEvent {
-                current_state: *previous_state,
-                new_state: player.state,
-            });
-        }
-        *previous_state = player.state;
-    }
-}
+type StateQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static mut Player,
+        &'static Direction,
+        &'static Momentum,
+        &'static mut Transform,
+        &'static Gravity,
+        Has<Grounded>,
+    ),
+>;
 
 fn transition_player_state(
     mut commands: Commands,
     mut animation_transitions: EventWriter<AnimationTransitionEvent>,
     animation_cache: Res<PlayerAnimationCache>,
     particles: Res<crate::particles::ParticleCache>,
-    mut player_query: Query<(Entity, &mut Player, &Direction, &Transform, Has<Grounded>)>,
+    mut player_query: StateQuery,
 ) {
-    for (entity, mut player, direction, transform, is_grounded) in &mut player_query {
+    for (entity, mut player, direction, momentum, mut transform, gravity, is_grounded) in
+        &mut player_query
+    {
         if is_grounded {
-            if direction.is_active() {
-                if player.state != PlayerState::Running {
+            match player.state {
+                PlayerState::LongJumping => {
                     player.state = PlayerState::Running;
-                    animation_transitions.send(AnimationTransitionEvent {
-                        entity,
-                        clip: animation_cache.run.clone_weak(),
-                        transition: Duration::from_secs_f32(0.2),
-                    });
+                }
+                PlayerState::Walljumping => {
+                    player.state = PlayerState::Freefall;
+                }
+                PlayerState::Knockback => {
+                    player.state = PlayerState::Idle;
+                }
+                PlayerState::Backflipping => {
+                    player.state = PlayerState::Freefall;
+                }
+                PlayerState::Diving => {
+                    player.state = PlayerState::BellySliding;
+                    commands
+                        .entity(entity)
+                        .insert(Coasting::new(BELLY_SLIDE_FRICTION));
 
                     commands.spawn(OneTimeParticleBundle::new(
                         transform.translation,
@@ -221,6 +533,45 @@ fn transition_player_state(
                         particles.dust.clone_weak(),
                     ));
                 }
+                PlayerState::BellySliding if momentum.get() < BELLY_SLIDE_STOP_SPEED.into() => {
+                    player.state = PlayerState::Idle;
+                    stand_upright(&mut transform, gravity);
+                    commands.entity(entity).remove::<Coasting>();
+                    animation_transitions.send(AnimationTransitionEvent {
+                        entity,
+                        clip: animation_cache.idle.clone_weak(),
+                        transition: Duration::from_secs_f32(0.3),
+                    });
+                }
+                _ => (),
+            }
+        }
+
+        if is_grounded && player.is_free_moving() {
+            if direction.is_active() {
+                let (state, clip) = if direction.is_full_tilt() {
+                    (PlayerState::Running, &animation_cache.run)
+                } else {
+                    // uli only has idle and run clips, walking plays the run cycle
+                    (PlayerState::Walking, &animation_cache.run)
+                };
+
+                if player.state != state {
+                    player.state = state;
+                    animation_transitions.send(AnimationTransitionEvent {
+                        entity,
+                        clip: clip.clone_weak(),
+                        transition: Duration::from_secs_f32(0.2),
+                    });
+
+                    if state == PlayerState::Running {
+                        commands.spawn(OneTimeParticleBundle::new(
+                            transform.translation,
+                            4.0,
+                            particles.dust.clone_weak(),
+                        ));
+                    }
+                }
             } else {
                 if player.state != PlayerState::Idle {
                     player.state = PlayerState::Idle;
@@ -235,21 +586,682 @@ fn transition_player_state(
     }
 }
 
+type JumpQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static mut Player,
+        &'static mut FixedMotion,
+        &'static mut Momentum,
+        &'static Speed,
+        &'static Gravity,
+        &'static mut PlayerMoves,
+        &'static TickActions,
+        Has<Grounded>,
+    ),
+    With<Player>,
+>;
+
+pub fn handle_jump(mut commands: Commands, mut player_query: JumpQuery) {
+    for (
+        entity,
+        mut player,
+        mut motion,
+        mut momentum,
+        speed,
+        gravity,
+        mut moves,
+        action,
+        is_grounded,
+    ) in &mut player_query
+    {
+        if !action.just_pressed(PlayerAction::Jump)
+            || !player.is_free_moving()
+            || !(is_grounded || moves.coyote_timer.take().is_some())
+        {
+            continue;
+        }
+
+        if action.pressed(PlayerAction::Crouch)
+            && player.state == PlayerState::Running
+            && speed.fraction_of_max() >= LONG_JUMP_SPEED_FRACTION
+        {
+            moves.jump_stage = 0;
+            gravity.set_rise(&mut motion, LONG_JUMP_VELOCITY.into());
+            let boosted_momentum = momentum.get().scale(LONG_JUMP_BOOST);
+            momentum.set(boosted_momentum);
+            player.state = PlayerState::LongJumping;
+        } else {
+            moves.advance_jump_stage(momentum.get());
+            gravity.set_rise(&mut motion, moves.jump_velocity());
+            player.state = PlayerState::Rising;
+        }
+        commands.entity(entity).remove::<Grounded>();
+    }
+}
+
+type DiveQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static mut Player,
+        &'static mut Transform,
+        &'static mut FixedMotion,
+        &'static mut Momentum,
+        &'static Gravity,
+        &'static mut PlayerMoves,
+        &'static TickActions,
+        Has<Grounded>,
+    ),
+>;
+
+fn handle_dive(
+    mut commands: Commands,
+    mut animation_transitions: EventWriter<AnimationTransitionEvent>,
+    animation_cache: Res<PlayerAnimationCache>,
+    mut player_query: DiveQuery,
+) {
+    for (
+        entity,
+        mut player,
+        mut transform,
+        mut motion,
+        mut momentum,
+        gravity,
+        mut moves,
+        action,
+        is_grounded,
+    ) in &mut player_query
+    {
+        if player.state == PlayerState::BellySliding && action.just_pressed(PlayerAction::Jump) {
+            player.state = PlayerState::Rising;
+            gravity.set_rise(&mut motion, DIVE_RECOVER_VELOCITY.into());
+            stand_upright(&mut transform, gravity);
+            commands
+                .entity(entity)
+                .remove::<Coasting>()
+                .remove::<Grounded>();
+            continue;
+        }
+
+        let can_dive = match player.state {
+            PlayerState::Walking | PlayerState::Running | PlayerState::Carrying => is_grounded,
+            PlayerState::Rising
+            | PlayerState::Freefall
+            | PlayerState::LongJumping
+            | PlayerState::Walljumping => !is_grounded,
+            _ => false,
+        };
+
+        if can_dive && action.just_pressed(PlayerAction::Dive) {
+            player.state = PlayerState::Diving;
+            moves.jump_stage = 0;
+            let rise = gravity.rise(&motion).max(DIVE_VELOCITY.into());
+            gravity.set_rise(&mut motion, rise);
+            let dive_momentum = momentum.get().max(DIVE_SPEED.into());
+            momentum.set(dive_momentum);
+            transform.rotate_local_x(-DIVE_TILT);
+            commands.entity(entity).remove::<Grounded>();
+
+            animation_transitions.send(AnimationTransitionEvent {
+                entity,
+                clip: animation_cache.idle.clone_weak(),
+                transition: Duration::from_secs_f32(0.1),
+            });
+        }
+    }
+}
+
+type WallKickQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static mut Player,
+        &'static mut Transform,
+        &'static mut FixedMotion,
+        &'static Momentum,
+        &'static Collider,
+        &'static Gravity,
+        &'static mut PlayerMoves,
+        &'static TickActions,
+    ),
+    Without<Grounded>,
+>;
+
+fn handle_wall_kick(
+    fixed_time: Res<FixedTime>,
+    mut collision_events: EventReader<CharacterCollisionEvent>,
+    rapier_context: Res<RapierContext>,
+    mut player_query: WallKickQuery,
+) {
+    let collisions: Vec<(Entity, Entity, Vec3)> = collision_events
+        .iter()
+        .map(|event| (event.entity, event.other, event.normal))
+        .collect();
+
+    for (
+        entity,
+        mut player,
+        mut transform,
+        mut motion,
+        momentum,
+        collider,
+        gravity,
+        mut moves,
+        action,
+    ) in &mut player_query
+    {
+        if !matches!(
+            player.state,
+            PlayerState::Rising
+                | PlayerState::Freefall
+                | PlayerState::LongJumping
+                | PlayerState::Walljumping
+        ) {
+            continue;
+        }
+
+        moves.wall_kick_window.tick(fixed_time.period);
+
+        let flat_forward = gravity.flatten(transform.forward());
+
+        if momentum.get() >= WALL_KICK_SPEED.into() {
+            let filter = QueryFilter::exclude_dynamic()
+                .exclude_sensors()
+                .exclude_collider(entity);
+
+            let shape_result = rapier_context.cast_shape(
+                transform.translation,
+                Quat::IDENTITY,
+                flat_forward,
+                collider,
+                WALL_CHECK_DISTANCE,
+                filter,
+            );
+
+            let cast_hit = shape_result
+                .filter(|(_, hit)| hit.status != TOIStatus::Penetrating)
+                .map(|(wall, hit)| (wall, hit.normal1));
+            let controller_hit = collisions
+                .iter()
+                .find(|(character, ..)| *character == entity)
+                .map(|(_, wall, normal)| (*wall, *normal));
+
+            if let Some((wall, normal)) = cast_hit.or(controller_hit) {
+                if normal.dot(gravity.up).abs() < 0.3 && moves.kicked_wall != Some(wall) {
+                    moves.touch_wall(wall, normal);
+                }
+            }
+        }
+
+        if action.just_pressed(PlayerAction::Jump) && !moves.wall_kick_window.finished() {
+            if let Some((wall, normal)) = moves.wall_contact.take() {
+                let reflected = flat_forward - 2.0 * flat_forward.dot(normal) * normal;
+                let kick_direction = gravity.flatten(reflected);
+                if kick_direction != Vec3::ZERO {
+                    transform.look_to(kick_direction, gravity.up);
+                }
+
+                gravity.set_rise(&mut motion, WALL_KICK_VELOCITY.into());
+                player.state = PlayerState::Walljumping;
+                moves.kicked_wall = Some(wall);
+            }
+        }
+    }
+}
+
+type SkidQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static mut Player,
+        &'static mut Transform,
+        &'static mut FixedMotion,
+        &'static mut Momentum,
+        &'static mut Speed,
+        &'static Direction,
+        &'static Gravity,
+        &'static mut PlayerMoves,
+        &'static TickActions,
+    ),
+    With<Grounded>,
+>;
+
+fn handle_skid(
+    mut commands: Commands,
+    mut animation_transitions: EventWriter<AnimationTransitionEvent>,
+    animation_cache: Res<PlayerAnimationCache>,
+    particles: Res<crate::particles::ParticleCache>,
+    mut player_query: SkidQuery,
+) {
+    for (
+        entity,
+        mut player,
+        mut transform,
+        mut motion,
+        mut momentum,
+        mut speed,
+        direction,
+        gravity,
+        mut moves,
+        action,
+    ) in &mut player_query
+    {
+        let flat_forward = gravity.flatten(transform.forward());
+        let turn_direction = direction.get().normalize_or_zero();
+
+        match player.state {
+            PlayerState::Running
+                if direction.is_active()
+                    && speed.fraction_of_max() >= SKID_SPEED_FRACTION
+                    && flat_forward.angle_between(turn_direction) > SKID_ANGLE =>
+            {
+                player.state = PlayerState::Skidding;
+                commands.entity(entity).insert(Coasting::new(SKID_FRICTION));
+                commands.spawn(OneTimeParticleBundle::new(
+                    transform.translation,
+                    4.0,
+                    particles.dust.clone_weak(),
+                ));
+            }
+            PlayerState::Skidding => {
+                if action.just_pressed(PlayerAction::Jump) {
+                    if turn_direction != Vec3::ZERO {
+                        transform.look_to(turn_direction, gravity.up);
+                    }
+                    player.state = PlayerState::Rising;
+                    moves.jump_stage = 0;
+                    gravity.set_rise(&mut motion, SIDE_FLIP_VELOCITY.into());
+                    momentum.set(SIDE_FLIP_SPEED.into());
+                    speed.reset();
+                    commands
+                        .entity(entity)
+                        .remove::<Coasting>()
+                        .remove::<Grounded>();
+                } else if momentum.get() <= SKID_STOP_SPEED.into() {
+                    if turn_direction != Vec3::ZERO {
+                        transform.look_to(turn_direction, gravity.up);
+                    }
+                    player.state = PlayerState::Idle;
+                    speed.reset();
+                    commands.entity(entity).remove::<Coasting>();
+                    animation_transitions.send(AnimationTransitionEvent {
+                        entity,
+                        clip: animation_cache.idle.clone_weak(),
+                        transition: Duration::from_secs_f32(0.2),
+                    });
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+type GroundPoundQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static mut Player,
+        &'static mut FixedMotion,
+        &'static mut Momentum,
+        &'static mut Drift,
+        &'static mut Gravity,
+        &'static mut PlayerMoves,
+        &'static TickActions,
+    ),
+    Without<Grounded>,
+>;
+
+pub fn handle_ground_pound(fixed_time: Res<FixedTime>, mut player_query: GroundPoundQuery) {
+    for (mut player, mut motion, mut momentum, mut drift, mut gravity, mut moves, action) in
+        &mut player_query
+    {
+        match player.state {
+            PlayerState::Rising
+            | PlayerState::Freefall
+            | PlayerState::LongJumping
+            | PlayerState::Walljumping
+                if action.just_pressed(PlayerAction::Crouch) =>
+            {
+                player.state = PlayerState::GroundPounding;
+                moves.ground_pound_timer =
+                    Timer::from_seconds(GROUND_POUND_HANG_TIME, TimerMode::Once);
+                motion.set_velocity(UVec::ZERO);
+                momentum.reset();
+                drift.0 = Vec3::ZERO;
+                gravity.scale = 0.0;
+            }
+            PlayerState::GroundPounding => {
+                moves.ground_pound_timer.tick(fixed_time.period);
+                if moves.ground_pound_timer.just_finished() {
+                    gravity.scale = GROUND_POUND_GRAVITY_SCALE;
+                    gravity.set_rise(&mut motion, Unit::from(-GROUND_POUND_SPEED));
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+type KnockbackQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static mut Player,
+        &'static mut Transform,
+        &'static mut FixedMotion,
+        &'static mut Momentum,
+        &'static mut Drift,
+        &'static mut Collider,
+        &'static mut Speed,
+        &'static mut Gravity,
+        &'static mut PlayerMoves,
+        &'static Health,
+    ),
+>;
+
+fn handle_knockback(
+    mut commands: Commands,
+    mut damage_events: EventReader<DamageEvent>,
+    mut player_query: KnockbackQuery,
+) {
+    // the invulnerability window only starts in take_damage, so later hits in the same frame
+    // would otherwise knock the player back again
+    let mut knocked_back = Vec::new();
+    for event in damage_events.iter() {
+        let Ok((
+            mut player,
+            mut transform,
+            mut motion,
+            mut momentum,
+            mut drift,
+            mut collider,
+            mut speed,
+            mut gravity,
+            mut moves,
+            health,
+        )) = player_query.get_mut(event.entity)
+        else {
+            continue;
+        };
+
+        if !event.knockback || health.is_invulnerable() || knocked_back.contains(&event.entity) {
+            continue;
+        }
+        knocked_back.push(event.entity);
+
+        if player.state == PlayerState::Crouching {
+            *collider = standing_collider();
+            speed.reset_max();
+        }
+
+        let away = gravity.flatten(transform.translation - event.source_position);
+        player.state = PlayerState::Knockback;
+        moves.ledge = None;
+        stand_upright(&mut transform, &gravity);
+        motion.set_velocity((away * KNOCKBACK_SPEED + gravity.up * KNOCKBACK_LIFT).into());
+        momentum.reset();
+        drift.0 = Vec3::ZERO;
+        gravity.scale = PLAYER_GRAVITY_SCALE;
+        commands
+            .entity(event.entity)
+            .remove::<(Grounded, Coasting)>();
+    }
+}
+
+type RespawnQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static mut Transform,
+        &'static mut GroundContact,
+        &'static mut Collider,
+        &'static mut Speed,
+        &'static mut Gravity,
+        &'static mut PlayerMoves,
+    ),
+    With<Player>,
+>;
+
+fn reset_on_respawn(
+    mut commands: Commands,
+    mut respawn_events: EventReader<RespawnEvent>,
+    mut player_query: RespawnQuery,
+) {
+    for event in respawn_events.iter() {
+        let Ok((
+            mut transform,
+            mut ground_contact,
+            mut collider,
+            mut speed,
+            mut gravity,
+            mut moves,
+        )) = player_query.get_mut(event.entity)
+        else {
+            continue;
+        };
+
+        *ground_contact = GroundContact::default();
+        *collider = standing_collider();
+        speed.reset_max();
+        gravity.scale = PLAYER_GRAVITY_SCALE;
+        stand_upright(&mut transform, &gravity);
+
+        *moves = PlayerMoves::default();
+
+        commands
+            .entity(event.entity)
+            .remove::<(Grounded, Coasting, HoldTilt)>();
+    }
+}
+
+pub fn standing_collider() -> Collider {
+    Collider::capsule_y(0.5, 0.5)
+}
+
+fn stand_upright(transform: &mut Transform, gravity: &Gravity) {
+    let flat_forward = gravity.flatten(transform.forward());
+    if flat_forward != Vec3::ZERO {
+        transform.look_to(flat_forward, gravity.up);
+    }
+}
+
+type SlopeQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static mut Player,
+        &'static mut Transform,
+        &'static mut FixedMotion,
+        &'static mut Momentum,
+        &'static mut Speed,
+        &'static Direction,
+        &'static GroundContact,
+        &'static Gravity,
+    ),
+    With<Grounded>,
+>;
+
+pub fn handle_slopes(
+    mut commands: Commands,
+    fixed_time: Res<FixedTime>,
+    mut animation_transitions: EventWriter<AnimationTransitionEvent>,
+    animation_cache: Res<PlayerAnimationCache>,
+    slide_surface_query: Query<(), With<SlideSurface>>,
+    mut player_query: SlopeQuery,
+) {
+    for (
+        entity,
+        mut player,
+        mut transform,
+        mut motion,
+        mut momentum,
+        mut speed,
+        direction,
+        contact,
+        gravity,
+    ) in &mut player_query
+    {
+        let normal = contact.normal;
+        let slope_angle = normal.angle_between(gravity.up);
+        let downhill = gravity.flatten(normal);
+        let on_slide_surface = contact
+            .entity
+            .is_some_and(|floor| slide_surface_query.contains(floor));
+
+        let is_walking_state = matches!(
+            player.state,
+            PlayerState::Idle | PlayerState::Walking | PlayerState::Running | PlayerState::Carrying
+        );
+
+        if is_walking_state && on_slide_surface && downhill != Vec3::ZERO {
+            player.state = PlayerState::ButtSliding;
+            commands
+                .entity(entity)
+                .insert(Coasting::new(BUTT_SLIDE_FRICTION));
+        } else if is_walking_state && slope_angle > MAX_WALKABLE_SLOPE {
+            player.state = PlayerState::Sliding;
+            commands
+                .entity(entity)
+                .insert(Coasting::new(SLIDE_FRICTION));
+        } else if (player.state == PlayerState::Sliding && slope_angle <= MAX_WALKABLE_SLOPE)
+            || (player.state == PlayerState::ButtSliding && !on_slide_surface)
+        {
+            player.state = PlayerState::Idle;
+            commands.entity(entity).remove::<Coasting>();
+            animation_transitions.send(AnimationTransitionEvent {
+                entity,
+                clip: animation_cache.idle.clone_weak(),
+                transition: Duration::from_secs_f32(0.3),
+            });
+        }
+
+        let flat_forward = gravity.flatten(transform.forward());
+
+        match player.state {
+            PlayerState::Sliding | PlayerState::ButtSliding => {
+                let slide = SLIDE_ACCEL * slope_angle.sin() * fixed_time.period.as_secs_f32();
+                let slide_momentum = momentum.get() + Unit::from(slide);
+
+                if player.state == PlayerState::ButtSliding {
+                    momentum.set(slide_momentum.max(BUTT_SLIDE_MIN_SPEED.into()));
+                    let steer_target =
+                        (downhill + direction.get() * BUTT_SLIDE_STEER).normalize_or_zero();
+                    if steer_target != Vec3::ZERO {
+                        let target_rotation = Transform::default()
+                            .looking_to(steer_target, gravity.up)
+                            .rotation;
+                        transform.rotation = transform
+                            .rotation
+                            .slerp(target_rotation, fixed_time.period.as_secs_f32() * 6.0);
+                    }
+                } else {
+                    momentum.set(slide_momentum);
+                    if downhill != Vec3::ZERO {
+                        transform.look_to(downhill, gravity.up);
+                    }
+                }
+            }
+            PlayerState::Idle
+            | PlayerState::Walking
+            | PlayerState::Running
+            | PlayerState::Carrying
+                if direction.is_active() =>
+            {
+                let downhill_amount = flat_forward.dot(downhill);
+                let gain = downhill_amount
+                    * SLOPE_SPEED_GAIN
+                    * slope_angle.sin()
+                    * fixed_time.period.as_secs_f32();
+                speed.apply_speed(gain.into());
+            }
+            _ => (),
+        }
+
+        let flat_velocity = motion.velocity() - UVec::along(gravity.up, gravity.rise(&motion));
+        if flat_velocity != UVec::ZERO && slope_angle > MIN_SLOPE {
+            let along_floor = flat_velocity - UVec::along(normal, flat_velocity.project(normal));
+            motion.set_velocity(along_floor.scale_to(flat_velocity.length()));
+        }
+    }
+}
+
+fn lock_tilt(mut commands: Commands, player_query: Query<(Entity, &Player, Has<HoldTilt>)>) {
+    for (entity, player, has_hold_tilt) in &player_query {
+        let holds_tilt = matches!(
+            player.state,
+            PlayerState::Diving | PlayerState::BellySliding | PlayerState::Hanging
+        );
+
+        if holds_tilt && !has_hold_tilt {
+            commands.entity(entity).insert(HoldTilt);
+        } else if !holds_tilt && has_hold_tilt {
+            commands.entity(entity).remove::<HoldTilt>();
+        }
+    }
+}
+
+fn handle_jump_window(
+    fixed_time: Res<FixedTime>,
+    mut player_query: Query<(&mut PlayerMoves, &Momentum), With<Grounded>>,
+) {
+    for (mut moves, momentum) in &mut player_query {
+        moves.jump_window.tick(fixed_time.period);
+        if moves.jump_window.finished() || momentum.get() < JUMP_CHAIN_SPEED.into() {
+            moves.jump_stage = 0;
+        }
+    }
+}
+
 pub struct PlayerPlugin;
 
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(PlayerData::default())
+            .add_event::<LandingEvent>()
             .add_systems(OnEnter(GameState::Gameplay), spawn_player)
             .add_systems(
                 Update,
+                (play_idle_animation, update_player_data).run_if(in_state(GameState::Gameplay)),
+            )
+            .add_systems(
+                FixedUpdate,
                 (
                     handle_grounded,
-                    play_idle_animation,
-                    update_player_data,
                     set_player_direction,
+                    handle_air_control,
                     transition_player_state,
+                    handle_jump_window.before(handle_jump),
+                    handle_jump.after(transition_player_state),
+                    handle_coyote_time.before(handle_jump),
+                    handle_airborne_state.after(handle_jump),
+                    handle_dive.after(handle_jump),
+                    handle_wall_kick.after(handle_jump),
+                    handle_skid.after(handle_jump).after(set_player_direction),
+                    handle_ground_pound.after(handle_jump),
+                    handle_landing_events.after(handle_grounded),
+                    handle_slopes
+                        .after(handle_grounded)
+                        .before(handle_jump)
+                        .before(handle_dive)
+                        .before(handle_skid),
+                )
+                    .in_set(MotionSet::React)
+                    .run_if(in_state(GameState::Gameplay)),
+            )
+            .add_systems(
+                FixedUpdate,
+                (
+                    handle_knockback.after(detect_hazards).before(take_damage),
+                    reset_on_respawn.after(take_damage),
+                    lock_tilt.after(handle_dive).after(handle_ledges),
                 )
+                    .in_set(MotionSet::React)
                     .run_if(in_state(GameState::Gameplay)),
             );
     }
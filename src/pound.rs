@@ -0,0 +1,113 @@
+use bevy::prelude::*;
+use std::time::Duration;
+
+use crate::{
+    animation::AnimationTransitionEvent,
+    assets::PlayerAnimationCache,
+    core::GameState,
+    particles::{OneTimeParticleBundle, ParticleCache},
+    physics::{FixedMotion, Gravity, Grounded, MotionSet},
+    player::{
+        handle_grounded, handle_slopes, LandingEvent, Player, PlayerState, PLAYER_GRAVITY_SCALE,
+    },
+};
+
+#[derive(Event)]
+pub struct GroundPoundEvent {
+    pub entity: Entity,
+    pub surface: Entity,
+    pub position: Vec3,
+}
+
+#[derive(Component)]
+pub enum PoundReaction {
+    Switch { pressed: bool },
+    Breakable,
+    Bounce(f32),
+}
+
+fn handle_ground_pound_landing(
+    mut landing_events: EventReader<LandingEvent>,
+    mut ground_pound_events: EventWriter<GroundPoundEvent>,
+    mut animation_transitions: EventWriter<AnimationTransitionEvent>,
+    animation_cache: Res<PlayerAnimationCache>,
+    mut player_query: Query<(&mut Player, &mut Gravity)>,
+) {
+    for event in landing_events.iter() {
+        let Ok((mut player, mut gravity)) = player_query.get_mut(event.entity) else {
+            continue;
+        };
+
+        if player.state == PlayerState::GroundPounding {
+            player.state = PlayerState::Idle;
+            gravity.scale = PLAYER_GRAVITY_SCALE;
+
+            ground_pound_events.send(GroundPoundEvent {
+                entity: event.entity,
+                surface: event.surface,
+                position: event.position,
+            });
+            animation_transitions.send(AnimationTransitionEvent {
+                entity: event.entity,
+                clip: animation_cache.idle.clone_weak(),
+                transition: Duration::from_secs_f32(0.1),
+            });
+        }
+    }
+}
+
+fn handle_pound_reactions(
+    mut commands: Commands,
+    mut ground_pound_events: EventReader<GroundPoundEvent>,
+    particles: Res<ParticleCache>,
+    mut reaction_query: Query<(&mut PoundReaction, &mut Transform), Without<Player>>,
+    mut player_query: Query<(&mut Player, &mut FixedMotion, &Gravity)>,
+) {
+    for event in ground_pound_events.iter() {
+        commands.spawn(OneTimeParticleBundle::new(
+            event.position,
+            2.0,
+            particles.dust_ring.clone_weak(),
+        ));
+
+        let Ok((mut reaction, mut transform)) = reaction_query.get_mut(event.surface) else {
+            continue;
+        };
+
+        match *reaction {
+            PoundReaction::Switch { pressed: false } => {
+                *reaction = PoundReaction::Switch { pressed: true };
+                transform.translation.y -= 0.15;
+            }
+            PoundReaction::Switch { pressed: true } => (),
+            PoundReaction::Breakable => {
+                commands.entity(event.surface).despawn_recursive();
+            }
+            PoundReaction::Bounce(bounce_velocity) => {
+                if let Ok((mut player, mut motion, gravity)) = player_query.get_mut(event.entity) {
+                    player.state = PlayerState::Rising;
+                    gravity.set_rise(&mut motion, bounce_velocity.into());
+                    commands.entity(event.entity).remove::<Grounded>();
+                }
+            }
+        }
+    }
+}
+
+pub struct PoundPlugin;
+
+impl Plugin for PoundPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<GroundPoundEvent>().add_systems(
+            FixedUpdate,
+            (
+                handle_ground_pound_landing.after(handle_grounded),
+                handle_pound_reactions
+                    .after(handle_ground_pound_landing)
+                    .after(handle_slopes),
+            )
+                .in_set(MotionSet::React)
+                .run_if(in_state(GameState::Gameplay)),
+        );
+    }
+}
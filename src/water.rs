@@ -0,0 +1,282 @@
+use bevy::ecs::query::Has;
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use crate::{
+    assets::MovementProfileCache,
+    camera::MainCamera,
+    core::{GameState, Unit},
+    health::{take_damage, DamageEvent, Health},
+    input::{PlayerAction, TickActions},
+    particles::{OneTimeParticleBundle, ParticleCache},
+    physics::{
+        Coasting, FixedMotion, Gravity, Grounded, Momentum, MotionSet, MovementProfile, Speed,
+    },
+    player::{
+        get_direction_in_camera_space, Player, PlayerData, PlayerState, PLAYER_GRAVITY_SCALE,
+    },
+};
+
+const FLOAT_DEPTH: f32 = 0.4;
+const HEAD_HEIGHT: f32 = 0.7;
+const BUOYANCY: f32 = 6.0;
+const MAX_FLOAT_SPEED: f32 = 3.0;
+const DIVE_SPEED: f32 = 3.0;
+const WATER_DRAG: f32 = 3.0;
+const SWIM_STROKE: f32 = 4.0;
+const WATER_JUMP_VELOCITY: f32 = 12.0;
+const SWIM_TURN_SPEED: f32 = 6.0;
+const BREATH_REFILL_RATE: f32 = 4.0;
+
+#[derive(Component)]
+pub struct WaterVolume {
+    /// A point on the waterline
+    pub surface: Vec3,
+}
+
+#[derive(Component)]
+pub struct Breath {
+    current: f32,
+    max: f32,
+}
+
+impl Breath {
+    pub fn new(max: f32) -> Self {
+        Breath { current: max, max }
+    }
+}
+
+#[derive(Component)]
+pub struct InWater {
+    surface: Vec3,
+}
+
+impl InWater {
+    fn height_above_surface(&self, position: Vec3, gravity: &Gravity) -> f32 {
+        (position - self.surface).dot(gravity.up)
+    }
+
+    fn point_on_surface(&self, position: Vec3, gravity: &Gravity) -> Vec3 {
+        position - gravity.up * self.height_above_surface(position, gravity)
+    }
+}
+
+type WaterQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static mut Player,
+        &'static Transform,
+        &'static mut Gravity,
+        &'static mut Handle<MovementProfile>,
+        Option<&'static InWater>,
+        Has<Grounded>,
+    ),
+>;
+
+fn handle_water_volumes(
+    mut commands: Commands,
+    rapier_context: Res<RapierContext>,
+    particles: Res<ParticleCache>,
+    movement_profiles: Res<MovementProfileCache>,
+    water_query: Query<&WaterVolume>,
+    mut player_query: WaterQuery,
+) {
+    for (entity, mut player, transform, mut gravity, mut profile, in_water, is_grounded) in
+        &mut player_query
+    {
+        let is_water = |candidate| water_query.contains(candidate);
+        let mut water = None;
+        rapier_context.intersections_with_point(
+            transform.translation,
+            QueryFilter::default().predicate(&is_water),
+            |other| {
+                water = water_query.get(other).ok();
+                false
+            },
+        );
+
+        match (water, in_water) {
+            (Some(volume), None) => {
+                let in_water = InWater {
+                    surface: volume.surface,
+                };
+                *profile = movement_profiles.swim.clone_weak();
+                commands.spawn(OneTimeParticleBundle::new(
+                    in_water.point_on_surface(transform.translation, &gravity),
+                    1.0,
+                    particles.splash.clone_weak(),
+                ));
+                commands.entity(entity).insert(in_water);
+            }
+            (None, Some(in_water)) => {
+                commands.entity(entity).remove::<InWater>();
+                *profile = movement_profiles.player.clone_weak();
+                gravity.scale = PLAYER_GRAVITY_SCALE;
+                if player.state == PlayerState::Swimming {
+                    player.state = PlayerState::Freefall;
+                }
+                commands.spawn(OneTimeParticleBundle::new(
+                    in_water.point_on_surface(transform.translation, &gravity),
+                    1.0,
+                    particles.splash.clone_weak(),
+                ));
+            }
+            _ => (),
+        }
+
+        // anything that leaves the player loose in the water, from jumping in to a ground pound
+        // running out, turns into swimming, except being knocked about
+        let is_swimming_state = matches!(
+            player.state,
+            PlayerState::Swimming | PlayerState::Knockback | PlayerState::Hanging
+        );
+        if water.is_some() && !is_grounded && !is_swimming_state {
+            player.state = PlayerState::Swimming;
+            gravity.scale = 0.0;
+            commands.entity(entity).remove::<Coasting>();
+        }
+
+        // touching the bottom of the pool puts the player back on their feet
+        if is_grounded && player.state == PlayerState::Swimming {
+            player.state = PlayerState::Idle;
+            gravity.scale = PLAYER_GRAVITY_SCALE;
+        }
+    }
+}
+
+type SwimQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static Player,
+        &'static mut Transform,
+        &'static mut FixedMotion,
+        &'static mut Momentum,
+        &'static mut Speed,
+        &'static Gravity,
+        &'static InWater,
+        &'static TickActions,
+    ),
+>;
+
+fn swim(
+    fixed_time: Res<FixedTime>,
+    mut player_query: SwimQuery,
+    camera_query: Query<&Transform, (With<MainCamera>, Without<Player>)>,
+) {
+    let camera_transform = camera_query.single();
+    let seconds = fixed_time.period.as_secs_f32();
+    for (player, mut transform, mut motion, mut momentum, mut speed, gravity, in_water, action) in
+        &mut player_query
+    {
+        if player.state != PlayerState::Swimming {
+            continue;
+        }
+
+        let depth = -FLOAT_DEPTH - in_water.height_above_surface(transform.translation, gravity);
+        let at_surface = depth.abs() < 0.3;
+
+        if action.just_pressed(PlayerAction::Jump) {
+            let stroke = if at_surface {
+                WATER_JUMP_VELOCITY.into()
+            } else {
+                gravity.rise(&motion) + SWIM_STROKE.into()
+            };
+            gravity.set_rise(&mut motion, stroke);
+        } else {
+            let target_rise = Unit::from(if action.pressed(PlayerAction::Crouch) {
+                -DIVE_SPEED
+            } else {
+                (depth * BUOYANCY).clamp(-MAX_FLOAT_SPEED, MAX_FLOAT_SPEED)
+            });
+            let rise = gravity.rise(&motion);
+            let drag = (WATER_DRAG * seconds).min(1.0);
+            gravity.set_rise(&mut motion, rise + (target_rise - rise).scale(drag));
+        }
+
+        let input = get_direction_in_camera_space(camera_transform, gravity, action);
+        if input.length() >= 0.3 {
+            let target = transform.translation + input;
+            let facing = transform.looking_at(target, gravity.up).rotation;
+            transform.rotation = transform
+                .rotation
+                .slerp(facing, (SWIM_TURN_SPEED * seconds).min(1.0));
+
+            speed.accelerate(fixed_time.period, seconds);
+            momentum.set(speed.current().scale(input.length()));
+        } else {
+            let drag = (WATER_DRAG * seconds).min(1.0);
+            let glided_momentum = momentum.get().scale(1.0 - drag);
+            if glided_momentum < Unit::from(0.1) {
+                momentum.reset();
+            } else {
+                momentum.set(glided_momentum);
+            }
+            speed.reset();
+        }
+    }
+}
+
+type BreathQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static Transform,
+        &'static Gravity,
+        &'static mut Breath,
+        &'static Health,
+        Option<&'static InWater>,
+    ),
+    With<Player>,
+>;
+
+fn handle_breath(
+    fixed_time: Res<FixedTime>,
+    mut player_data: ResMut<PlayerData>,
+    mut damage_events: EventWriter<DamageEvent>,
+    mut player_query: BreathQuery,
+) {
+    for (entity, transform, gravity, mut breath, health, in_water) in &mut player_query {
+        let head = transform.translation + gravity.up * HEAD_HEIGHT;
+        let is_underwater =
+            in_water.is_some_and(|water| water.height_above_surface(head, gravity) < 0.0);
+        player_data.underwater = is_underwater;
+
+        if !is_underwater {
+            breath.current = (breath.current
+                + BREATH_REFILL_RATE * fixed_time.period.as_secs_f32())
+            .min(breath.max);
+            continue;
+        }
+
+        breath.current = (breath.current - fixed_time.period.as_secs_f32()).max(0.0);
+        if breath.current == 0.0 && !health.is_invulnerable() {
+            damage_events.send(DamageEvent {
+                entity,
+                source_position: transform.translation,
+                amount: 1,
+                knockback: false,
+            });
+        }
+    }
+}
+
+pub struct WaterPlugin;
+
+impl Plugin for WaterPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            (
+                handle_water_volumes,
+                swim.after(handle_water_volumes),
+                handle_breath.before(take_damage),
+            )
+                .in_set(MotionSet::React)
+                .run_if(in_state(GameState::Gameplay)),
+        );
+    }
+}